@@ -0,0 +1,42 @@
+#[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+extern crate handlebars5 as handlebars;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use handlebars::Handlebars;
+use handlebars_concat::HandlebarsConcat;
+use serde_json::json;
+
+fn distinct(c: &mut Criterion) {
+    let mut h = Handlebars::new();
+    h.register_helper("concat", Box::new(HandlebarsConcat));
+
+    let mut group = c.benchmark_group("distinct");
+    group.sample_size(10);
+
+    for size in [10_000, 20_000] {
+        // every value appears twice, half of the buffer gets eliminated
+        let data = json!({ "arr": (0..size).map(|i| format!("value{}", i % (size / 2))).collect::<Vec<String>>() });
+
+        group.bench_with_input(BenchmarkId::new("array", size), &data, |b, data| {
+            b.iter(|| {
+                h.render_template(r#"{{concat arr distinct=true}}"#, black_box(data))
+                    .expect("Render error")
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("block", size), &data, |b, data| {
+            b.iter(|| {
+                h.render_template(
+                    r#"{{#concat arr distinct=true render_all=true}}{{this}}{{/concat}}"#,
+                    black_box(data),
+                )
+                .expect("Render error")
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, distinct);
+criterion_main!(benches);
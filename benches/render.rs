@@ -0,0 +1,48 @@
+#[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+extern crate handlebars5 as handlebars;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use handlebars::Handlebars;
+use handlebars_concat::HandlebarsConcat;
+use serde_json::json;
+
+fn render(c: &mut Criterion) {
+    let mut h = Handlebars::new();
+    h.register_helper("concat", Box::new(HandlebarsConcat));
+
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+
+    for size in [10_000, 20_000] {
+        // every item renders through the block template into its own value
+        let data = json!({
+            "arr": (0..size).map(|i| json!({"id": i, "name": format!("name{}", i)})).collect::<Vec<_>>(),
+            "obj": (0..size).map(|i| (format!("key{}", i), json!({"name": format!("name{}", i)}))).collect::<serde_json::Map<_, _>>()
+        });
+
+        group.bench_with_input(BenchmarkId::new("array", size), &data, |b, data| {
+            b.iter(|| {
+                h.render_template(
+                    r#"{{#concat arr render_all=true}}{{id}}:{{name}}{{/concat}}"#,
+                    black_box(data),
+                )
+                .expect("Render error")
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("object", size), &data, |b, data| {
+            b.iter(|| {
+                h.render_template(
+                    r#"{{#concat obj}}{{@key}}={{name}}{{/concat}}"#,
+                    black_box(data),
+                )
+                .expect("Render error")
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
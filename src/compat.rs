@@ -0,0 +1,28 @@
+use std::borrow::Cow;
+
+use handlebars::{Handlebars, RenderContext, Template};
+
+// partial or registered template by name
+#[cfg(feature = "handlebars6")]
+pub(crate) fn get_partial<'reg: 'rc, 'rc>(
+    rc: &RenderContext<'reg, 'rc>,
+    r: &'reg Handlebars<'reg>,
+    name: &str,
+) -> Option<Cow<'rc, Template>> {
+    rc.get_partial(name)
+        .or_else(|| r.get_template(name))
+        .map(Cow::Borrowed)
+}
+
+// inline partials of handlebars 5 do not outlive the borrow of the render context, so they are copied
+#[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+pub(crate) fn get_partial<'reg: 'rc, 'rc>(
+    rc: &RenderContext<'reg, 'rc>,
+    r: &'reg Handlebars<'reg>,
+    name: &str,
+) -> Option<Cow<'rc, Template>> {
+    match rc.get_partial(name) {
+        Some(partial) => Some(Cow::Owned(partial.clone())),
+        None => r.get_template(name).map(Cow::Borrowed),
+    }
+}
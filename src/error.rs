@@ -0,0 +1,255 @@
+use std::fmt;
+
+use handlebars::{RenderError, RenderErrorReason};
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors of the concat helper
+///
+/// # Usage
+///
+/// The errors are reported as [`RenderErrorReason::NestedError`], [`ConcatError::from_render_error`] recovers them from a [`RenderError`].
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::{ConcatError, HandlebarsConcat};
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("concat", Box::new(HandlebarsConcat));
+///
+/// let error = h.render_template(r#"{{concat a typo strict=true}}"#, &json!({"a": "x"})).expect_err("Render error expected");
+///
+/// assert!(matches!(ConcatError::from_render_error(&error), Some(ConcatError::UnresolvedParam { index: 1, .. })));
+/// ```
+///
+pub enum ConcatError {
+    /// Param at specific index and path could not be resolved, in strict mode
+    UnresolvedParam { index: usize, path: Option<String> },
+    /// JSON pointer could not be resolved in the param at specific index and path, in strict mode
+    UnresolvedPointer {
+        index: usize,
+        path: Option<String>,
+        pointer: String,
+    },
+    /// Hash parameters not accepted by the helper, in strict mode
+    UnknownHashParams(Vec<String>),
+    /// Hash parameter with a value that could not be used, e.g. invalid regular expression
+    InvalidHashParam {
+        name: &'static str,
+        value: String,
+        reason: String,
+    },
+    /// Script of specific hash parameter failed to evaluate
+    ScriptFailed { name: &'static str, reason: String },
+    /// More combinations than allowed by `max_combinations` of the product helper, unknown when they don't fit into usize
+    TooManyCombinations { count: Option<usize>, max: usize },
+    /// Rendering the param at specific index and path through the block template failed
+    RenderFailed {
+        index: usize,
+        path: Option<String>,
+        source: RenderError,
+    },
+}
+
+impl ConcatError {
+    /// Concat error reported through a render error, if any
+    pub fn from_render_error(error: &RenderError) -> Option<&ConcatError> {
+        match error.reason() {
+            RenderErrorReason::NestedError(nested) => nested.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+// param described by its path, if any, and index
+struct Param<'a>(usize, Option<&'a String>);
+
+impl fmt::Display for Param<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(path) => write!(f, "param \"{}\" at index {}", path, self.0),
+            None => write!(f, "param at index {}", self.0),
+        }
+    }
+}
+
+impl fmt::Display for ConcatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcatError::UnresolvedParam { index, path } => {
+                write!(
+                    f,
+                    "concat: {} could not be resolved",
+                    Param(*index, path.as_ref())
+                )
+            }
+            ConcatError::UnresolvedPointer {
+                index,
+                path,
+                pointer,
+            } => write!(
+                f,
+                "concat: pointer \"{}\" could not be resolved in {}",
+                pointer,
+                Param(*index, path.as_ref())
+            ),
+            ConcatError::UnknownHashParams(names) => write!(
+                f,
+                "concat: unknown hash parameters {}, accepted parameters are {}",
+                names.join(", "),
+                crate::HASH_PARAMS.join(", ")
+            ),
+            ConcatError::InvalidHashParam {
+                name,
+                value,
+                reason,
+            } => write!(
+                f,
+                "concat: invalid value \"{}\" of hash parameter \"{}\": {}",
+                value, name, reason
+            ),
+            ConcatError::ScriptFailed { name, reason } => {
+                write!(f, "concat: script in \"{}\" failed: {}", name, reason)
+            }
+            ConcatError::TooManyCombinations { count, max } => match count {
+                Some(count) => write!(
+                    f,
+                    "concat_product: {} combinations exceed the maximum of {}",
+                    count, max
+                ),
+                None => write!(
+                    f,
+                    "concat_product: combinations exceed the maximum of {}",
+                    max
+                ),
+            },
+            ConcatError::RenderFailed {
+                index,
+                path,
+                source,
+            } => write!(
+                f,
+                "concat: failed to render {}: {}",
+                Param(*index, path.as_ref()),
+                source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConcatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConcatError::RenderFailed { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConcatError> for RenderError {
+    fn from(error: ConcatError) -> Self {
+        RenderErrorReason::NestedError(Box::new(error)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use crate::HandlebarsConcat;
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": "x", "arr": [{"b": 1}], "obj": {"c": {}}});
+
+        let error = h
+            .render_template(r#"{{concat a typo strict=true}}"#, &data)
+            .expect_err("Failed to report missing param");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::UnresolvedParam { index: 1, path: Some(path) }) if path == "typo"
+            ),
+            "Failed to report missing param: {:?}",
+            error
+        );
+
+        let error = h
+            .render_template(r#"{{concat obj a pointer="/c/d" strict=true}}"#, &data)
+            .expect_err("Failed to report missing pointer");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::UnresolvedPointer { index: 0, path: Some(path), pointer }) if path == "obj" && pointer == "/c/d"
+            ),
+            "Failed to report missing pointer: {:?}",
+            error
+        );
+
+        let error = h
+            .render_template(r#"{{concat a seperator=", " strict=true}}"#, &data)
+            .expect_err("Failed to report unknown hash parameter");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::UnknownHashParams(names)) if names == &["seperator"]
+            ),
+            "Failed to report unknown hash parameter: {:?}",
+            error
+        );
+
+        let error = h
+            .render_template(r#"{{concat a pointer="c"}}"#, &data)
+            .expect_err("Failed to report invalid pointer");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::InvalidHashParam { name: "pointer", value, .. }) if value == "c"
+            ),
+            "Failed to report invalid pointer: {:?}",
+            error
+        );
+
+        let error = h
+            .render_template(
+                r#"{{#concat a arr render_all=true}}{{missing this}}{{/concat}}"#,
+                &data,
+            )
+            .expect_err("Failed to report block render error");
+        assert!(
+            error
+                .to_string()
+                .contains(r#"failed to render param "a" at index 0"#),
+            "Failed to describe block render error: {}",
+            error
+        );
+        match ConcatError::from_render_error(&error) {
+            Some(ConcatError::RenderFailed { index, source, .. }) => {
+                assert_eq!(*index, 0, "Failed to report param index");
+                assert!(
+                    matches!(source.reason(), RenderErrorReason::HelperNotFound(name) if name == "missing"),
+                    "Failed to keep source error: {:?}",
+                    source
+                );
+            }
+            error => panic!("Failed to report block render error: {:?}", error),
+        }
+
+        assert!(
+            ConcatError::from_render_error(
+                &h.render_template(r#"{{concat}"#, &data)
+                    .expect_err("Failed to report syntax error")
+            )
+            .is_none(),
+            "Failed to skip unrelated error"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use handlebars::{
+    BlockContext, Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output,
+    RenderContext, RenderErrorReason, Renderable,
+};
+
+use crate::{lookup_field, set_block_locals, BlockOutput};
+
+#[derive(Clone, Copy)]
+/// Group by helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsGroupBy;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("group_by", Box::new(HandlebarsGroupBy));
+///
+/// let data = json!({"arr": [
+///     {"name": "Apple", "kind": "fruit"},
+///     {"name": "Carrot", "kind": "vegetable"},
+///     {"name": "Pear", "kind": "fruit"}
+/// ]});
+///
+/// assert_eq!(
+///     h.render_template(r#"{{#group_by arr by="kind" separator="; "}}{{@group_key}}: {{#each this}}{{name}}{{#unless @last}}, {{/unless}}{{/each}}{{/group_by}}"#, &data).expect("Render error"),
+///     "fruit: Apple, Pear; vegetable: Carrot"
+/// );
+/// ```
+///
+/// # Behavior
+///
+/// The helper groups the items of a single array argument by the value of a field, keeping the order in which the groups first appear.
+/// Each group is rendered through the block template with the array of group items as `{{this}}`, and the results are joined.
+/// Without a block template only the group keys are joined. Items missing the field are grouped under an empty key.
+/// If there are no groups, the `{{else}}` block template is rendered instead, when present.
+///
+/// The block template has access to the `@group_key`, `@index`, `@first` and `@last` local variables.
+///
+/// # Hash parameters
+///
+/// * by: Field to group array items by, supporting dot separated paths. Required
+/// * separator: Set specific string to join groups with. Default is ","
+///
+pub struct HandlebarsGroupBy;
+
+impl HelperDef for HandlebarsGroupBy {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let param = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("group_by", 0))?;
+
+        let field = h
+            .hash_get("by")
+            .map(|by| by.render())
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("group_by", "by".to_string()))?;
+
+        let separator = if let Some(s) = h.hash_get("separator") {
+            s.render()
+        } else {
+            ",".to_string()
+        };
+
+        let items = match param.value() {
+            serde_json::Value::Null => &[][..],
+            serde_json::Value::Array(ar) => ar.as_slice(),
+            _ => {
+                return Err(RenderErrorReason::ParamTypeMismatchForName(
+                    "group_by",
+                    param.relative_path().cloned().unwrap_or_default(),
+                    "array".to_string(),
+                )
+                .into());
+            }
+        };
+
+        // groups in order of first appearance, with their positions by key
+        let mut groups: Vec<(String, Vec<serde_json::Value>)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+
+        for item in items {
+            let key = lookup_field(item, &field)
+                .map(|value| value.render())
+                .unwrap_or_default();
+
+            match positions.get(&key) {
+                Some(&position) => groups[position].1.push(item.clone()),
+                None => {
+                    positions.insert(key.clone(), groups.len());
+                    groups.push((key, vec![item.clone()]));
+                }
+            }
+        }
+
+        if groups.is_empty() {
+            if let Some(inverse) = h.inverse() {
+                return inverse.render(r, ctx, rc, out);
+            }
+
+            return Ok(());
+        }
+
+        let mut output = Vec::with_capacity(groups.len());
+        let mut content = BlockOutput::default();
+        let len = groups.len();
+
+        for (index, (key, group)) in groups.into_iter().enumerate() {
+            if let Some(template) = h.template() {
+                let mut block = BlockContext::new();
+                block.set_base_value(serde_json::Value::from(group));
+                block.set_local_var("group_key", serde_json::Value::from(key));
+                set_block_locals(&mut block, index, len);
+
+                rc.push_block(block);
+                template.render(r, ctx, rc, &mut content)?;
+                rc.pop_block();
+
+                output.push(content.take());
+            } else {
+                output.push(key);
+            }
+        }
+
+        out.write(&output.join(&separator))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("group_by", Box::new(HandlebarsGroupBy));
+
+        let data = json!({
+            "arr": [
+                {"name": "a", "meta": {"kind": "x"}},
+                {"name": "b", "meta": {"kind": "y"}},
+                {"name": "c", "meta": {"kind": "x"}},
+                {"name": "d"}
+            ],
+            "empty": []
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#group_by arr by="meta.kind" separator="|"}}{{@index}}{{@group_key}}:{{#each this}}{{name}}{{/each}}{{#if @last}}.{{/if}}{{/group_by}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0x:ac|1y:b|2:d.",
+            "Failed to group array items"
+        );
+        assert_eq!(
+            h.render_template(r#"{{group_by arr by="meta.kind"}}"#, &data)
+                .expect("Render error"),
+            "x,y,",
+            "Failed to join group keys"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#group_by empty by="name"}}{{@group_key}}{{else}}none{{/group_by}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to render else block"
+        );
+        assert!(
+            h.render_template(r#"{{group_by arr}}"#, &data).is_err(),
+            "Failed to require by parameter"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output, RenderContext,
+    RenderErrorReason,
+};
+
+#[derive(Clone, Copy)]
+/// Join helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsJoin;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("join", Box::new(HandlebarsJoin));
+///
+/// assert_eq!(h.render_template(r#"{{join arr}}"#, &json!({"arr": ["One", "Two"]})).expect("Render error"), "One,Two");
+/// assert_eq!(h.render_template(r#"{{join arr separator=", "}}"#, &json!({"arr": [1, 2, 3]})).expect("Render error"), "1, 2, 3");
+/// ```
+///
+/// # Behavior
+///
+/// A simpler alternative to `concat` for the common case. The helper accepts a single array argument and joins its items as strings.
+/// Missing and `null` arguments render nothing, while any other type of argument is a render error.
+///
+/// # Hash parameters
+///
+/// * separator: Set specific string to join elements with. Default is ","
+///
+pub struct HandlebarsJoin;
+
+impl HelperDef for HandlebarsJoin {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let param = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("join", 0))?;
+
+        let separator = if let Some(s) = h.hash_get("separator") {
+            s.render()
+        } else {
+            ",".to_string()
+        };
+
+        match param.value() {
+            serde_json::Value::Null => {}
+            serde_json::Value::Array(ar) => {
+                out.write(
+                    &ar.iter()
+                        .map(|item| item.render())
+                        .collect::<Vec<String>>()
+                        .join(&separator),
+                )?;
+            }
+            _ => {
+                return Err(RenderErrorReason::ParamTypeMismatchForName(
+                    "join",
+                    param.relative_path().cloned().unwrap_or_default(),
+                    "array".to_string(),
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("join", Box::new(HandlebarsJoin));
+
+        let data = json!({"arr": ["a", 1, true], "obj": {"a": 1}, "empty": []});
+
+        assert_eq!(
+            h.render_template(r#"{{join arr separator=" "}}"#, &data)
+                .expect("Render error"),
+            "a 1 true",
+            "Failed to join array"
+        );
+        assert_eq!(
+            h.render_template(r#"{{join empty}}{{join missing}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to join empty and missing arrays"
+        );
+        assert!(
+            h.render_template(r#"{{join obj}}"#, &data).is_err(),
+            "Failed to reject object argument"
+        );
+    }
+}
@@ -1,423 +1,7005 @@
+// handlebars 5 under the same name as handlebars 6, unless both are enabled
+#[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+extern crate handlebars5 as handlebars;
+
+#[cfg(not(any(feature = "handlebars5", feature = "handlebars6")))]
+compile_error!("either the \"handlebars6\" or the \"handlebars5\" feature must be enabled");
+
+mod compat;
+mod error;
+mod group_by;
+mod join;
+mod options;
+mod product;
+mod split;
+mod unique;
+mod zip;
+
+pub use error::ConcatError;
+pub use group_by::HandlebarsGroupBy;
+pub use join::HandlebarsJoin;
+pub use options::{concat_values, ConcatOptions};
+pub use product::HandlebarsProduct;
+pub use split::HandlebarsSplit;
+pub use unique::HandlebarsUnique;
+pub use zip::HandlebarsZip;
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use compat::get_partial;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
 use handlebars::{
-    BlockContext, Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output,
-    PathAndJson, RenderContext, Renderable, ScopedJson, StringOutput,
+    html_escape, BlockContext, Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender,
+    Output, PathAndJson, RenderContext, RenderError, RenderErrorReason, Renderable, ScopedJson,
+    Template,
 };
 
 const QUOTES_DOUBLE: &str = "\"";
 const QUOTES_SINGLE: &str = "\'";
+const KEY_VALUE_SEPARATOR: &str = "=";
+
+// hash parameters of optional features, rejected when used without them instead of silently doing nothing
+pub(crate) const FEATURE_PARAMS: &[(&str, bool, &str)] = &[
+    (
+        "exclude_match",
+        cfg!(feature = "regex"),
+        "requires the `regex` feature",
+    ),
+    (
+        "filter_script",
+        cfg!(feature = "rhai"),
+        "requires the `rhai` feature",
+    ),
+    (
+        "locale",
+        cfg!(feature = "icu"),
+        "requires the `icu` feature",
+    ),
+    (
+        "match",
+        cfg!(feature = "regex"),
+        "requires the `regex` feature",
+    ),
+    (
+        "normalize",
+        cfg!(feature = "unicode-normalization"),
+        "requires the `unicode-normalization` feature",
+    ),
+    (
+        "parallel",
+        cfg!(all(feature = "rayon", feature = "handlebars6")),
+        "requires the `rayon` feature along with `handlebars6`",
+    ),
+    (
+        "sort_script",
+        cfg!(feature = "rhai"),
+        "requires the `rhai` feature",
+    ),
+];
+
+// accepted hash parameters, validated in strict mode
+pub(crate) const HASH_PARAMS: &[&str] = &[
+    "bool_false",
+    "bool_true",
+    "case",
+    "chunk",
+    "chunk_separator",
+    "contains",
+    "count",
+    "count_duplicates",
+    "default",
+    "distinct",
+    "distinct_by",
+    "ellipsis",
+    "encode",
+    "ends_with",
+    "enumerate",
+    "escape",
+    "escape_output",
+    "exclude_match",
+    "filter_key_prefix",
+    "filter_key_suffix",
+    "filter_script",
+    "flatten",
+    "format",
+    "frequency",
+    "gt",
+    "gte",
+    "item_max_length",
+    "indent",
+    "item_template",
+    "json_encode",
+    "keep_empty",
+    "key_value_separator",
+    "last_separator",
+    "leading_separator",
+    "leading_zeros",
+    "limit",
+    "locale",
+    "map",
+    "lt",
+    "lte",
+    "match",
+    "max_length",
+    "mode",
+    "normalize",
+    "null",
+    "number_pad",
+    "number_separator",
+    "number_start",
+    "number_style",
+    "offset",
+    "ordered",
+    "overflow",
+    "pad_char",
+    "pad_end",
+    "pad_start",
+    "pair_separator",
+    "parallel",
+    "partial",
+    "pluck",
+    "pointer",
+    "precision",
+    "prefix",
+    "quote_char",
+    "quote_end",
+    "quote_escape",
+    "quotes",
+    "recursive",
+    "render_all",
+    "reverse",
+    "separator",
+    "single_quote",
+    "slugify",
+    "sort",
+    "sort_by",
+    "sort_keys",
+    "sort_script",
+    "starts_with",
+    "strict",
+    "suffix",
+    "thousands_separator",
+    "trailing_separator",
+    "trim",
+    "trim_block",
+    "unescape_separator",
+    "where",
+    "window",
+    "wrap_end",
+    "wrap_start",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortMode {
+    Ascending,
+    Descending,
+    Numeric,
+    Natural,
+}
+
+impl SortMode {
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode.to_ascii_lowercase().as_str() {
+            "true" | "asc" => Some(SortMode::Ascending),
+            "desc" => Some(SortMode::Descending),
+            "numeric" => Some(SortMode::Numeric),
+            "natural" => Some(SortMode::Natural),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ObjectMode {
+    Keys,
+    Values,
+    Entries,
+}
+
+impl ObjectMode {
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "keys" => Some(ObjectMode::Keys),
+            "values" => Some(ObjectMode::Values),
+            "entries" => Some(ObjectMode::Entries),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputMode {
+    UnorderedList,
+    OrderedList,
+    Markdown,
+    MarkdownOrdered,
+    Csv,
+    Sql,
+    Path,
+}
+
+impl OutputMode {
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "ul" => Some(OutputMode::UnorderedList),
+            "ol" => Some(OutputMode::OrderedList),
+            "markdown" => Some(OutputMode::Markdown),
+            "csv" => Some(OutputMode::Csv),
+            "sql" => Some(OutputMode::Sql),
+            "path" => Some(OutputMode::Path),
+            _ => None,
+        }
+    }
+
+    // markup values are not to be escaped again
+    pub(crate) fn is_html(self) -> bool {
+        matches!(self, OutputMode::UnorderedList | OutputMode::OrderedList)
+    }
+
+    // HTML and Markdown lists, as opposed to joining the values with a separator
+    pub(crate) fn is_markup(self) -> bool {
+        matches!(
+            self,
+            OutputMode::UnorderedList
+                | OutputMode::OrderedList
+                | OutputMode::Markdown
+                | OutputMode::MarkdownOrdered
+        )
+    }
+
+    pub(crate) fn join(self, output: &[String], separator: &str) -> String {
+        match self {
+            OutputMode::UnorderedList => join_list("ul", output),
+            OutputMode::OrderedList => join_list("ol", output),
+            OutputMode::Markdown => output
+                .iter()
+                .map(|item| format!("- {}", item))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            OutputMode::MarkdownOrdered => output
+                .iter()
+                .enumerate()
+                .map(|(index, item)| format!("{}. {}", index + 1, item))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            OutputMode::Csv => output
+                .iter()
+                .map(|item| csv_field(item, separator))
+                .collect::<Vec<String>>()
+                .join(separator),
+            // the values are turned into SQL literals while collecting them, knowing which ones are numbers
+            OutputMode::Sql => output.join(separator),
+            OutputMode::Path => join_path(output, separator),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DistinctMode {
+    CaseSensitive,
+    CaseInsensitive,
+}
+
+impl DistinctMode {
+    pub(crate) fn parse(mode: &str) -> Self {
+        if mode.split_whitespace().any(|token| token == "ci") {
+            DistinctMode::CaseInsensitive
+        } else {
+            DistinctMode::CaseSensitive
+        }
+    }
+
+    // later occurrences of duplicates take the place of the earlier ones
+    pub(crate) fn is_keep_last(mode: &str) -> bool {
+        mode.split_whitespace().any(|token| token == "last")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CaseMode {
+    Lower,
+    Upper,
+    Capitalize,
+}
+
+impl CaseMode {
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "lower" => Some(CaseMode::Lower),
+            "upper" => Some(CaseMode::Upper),
+            "capitalize" => Some(CaseMode::Capitalize),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn apply(self, value: String) -> String {
+        match self {
+            CaseMode::Lower => value.to_lowercase(),
+            CaseMode::Upper => value.to_uppercase(),
+            CaseMode::Capitalize => {
+                let mut chars = value.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => value,
+                }
+            }
+        }
+    }
+}
+
+// built-in transforms of the `map` pipeline
+#[derive(Clone, Copy)]
+pub(crate) enum Transform {
+    Trim,
+    Case(CaseMode),
+    Slug,
+    #[cfg(feature = "unicode-normalization")]
+    Normalize,
+}
+
+impl Transform {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "trim" => Some(Transform::Trim),
+            "slug" => Some(Transform::Slug),
+            #[cfg(feature = "unicode-normalization")]
+            "normalize" => Some(Transform::Normalize),
+            _ => CaseMode::parse(name).map(Transform::Case),
+        }
+    }
+
+    pub(crate) fn apply(self, value: String) -> String {
+        match self {
+            Transform::Trim => value.trim().to_string(),
+            Transform::Case(case) => case.apply(value),
+            Transform::Slug => slugify(&value),
+            #[cfg(feature = "unicode-normalization")]
+            Transform::Normalize => {
+                unicode_normalization::UnicodeNormalization::nfc(value.as_str()).collect()
+            }
+        }
+    }
+}
+
+// step of the `map` pipeline, the registered helper of that name, or the built-in transform when there is none
+pub(crate) struct MapStep {
+    pub(crate) name: String,
+    pub(crate) template: Template,
+    pub(crate) transform: Option<Transform>,
+    // whether the helper is registered, known after its first call
+    pub(crate) registered: Cell<Option<bool>>,
+}
+
+impl MapStep {
+    pub(crate) fn parse(step: &str) -> Result<Self, ConcatError> {
+        // plain helper names only, anything else would end up as template code
+        let is_name = step.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && step
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_name {
+            return Err(ConcatError::InvalidHashParam {
+                name: "map",
+                value: step.to_string(),
+                reason: "expected helper or transform name".to_string(),
+            });
+        }
+
+        let template = Template::compile(&format!("{{{{{{{} this}}}}}}", step)).map_err(|e| {
+            ConcatError::InvalidHashParam {
+                name: "map",
+                value: step.to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+
+        Ok(MapStep {
+            name: step.to_string(),
+            template,
+            transform: Transform::parse(step),
+            registered: Cell::new(None),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuoteEscape {
+    Backslash,
+    Double,
+    None,
+}
+
+impl QuoteEscape {
+    pub(crate) fn parse(mode: &str) -> Self {
+        match mode {
+            "double" => QuoteEscape::Double,
+            "none" => QuoteEscape::None,
+            _ => QuoteEscape::Backslash,
+        }
+    }
+}
+
+// values wrapped in quotation marks
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuoteMode {
+    All,
+    Strings,
+    Auto,
+    Backtick,
+    Bracket,
+}
+
+impl QuoteMode {
+    pub(crate) fn parse(mode: &str) -> Self {
+        match mode {
+            "strings" => QuoteMode::Strings,
+            "auto" => QuoteMode::Auto,
+            "backtick" => QuoteMode::Backtick,
+            "bracket" => QuoteMode::Bracket,
+            _ => QuoteMode::All,
+        }
+    }
+
+    // opening and closing delimiters of SQL identifiers, replacing the quotation marks
+    pub(crate) fn identifier_delimiters(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            QuoteMode::Backtick => Some(("`", "`")),
+            QuoteMode::Bracket => Some(("[", "]")),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn applies(
+        self,
+        value: &str,
+        literal: bool,
+        separator: &str,
+        (open, close): (&str, &str),
+    ) -> bool {
+        match self {
+            QuoteMode::All | QuoteMode::Backtick | QuoteMode::Bracket => true,
+            QuoteMode::Strings => !(literal && is_json_literal(value)),
+            QuoteMode::Auto => {
+                (!separator.is_empty() && value.contains(separator))
+                    || (!open.is_empty() && value.contains(open))
+                    || (!close.is_empty() && value.contains(close))
+                    || value.contains(char::is_whitespace)
+            }
+        }
+    }
+}
+
+// valid JSON numbers, booleans and null, which are not strings in JSON, SQL and most other languages
+pub(crate) fn is_json_literal(value: &str) -> bool {
+    matches!(value, "true" | "false" | "null") || value.parse::<serde_json::Number>().is_ok()
+}
+
+// source values rendering into literals rather than strings, as long as their rendering stays a valid literal
+pub(crate) fn is_literal(value: &serde_json::Value) -> bool {
+    matches!(
+        value,
+        serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_)
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NumberStyle {
+    Arabic,
+    Alpha,
+    Roman,
+}
+
+impl NumberStyle {
+    pub(crate) fn parse(style: &str) -> Self {
+        match style {
+            "alpha" => NumberStyle::Alpha,
+            "roman" => NumberStyle::Roman,
+            _ => NumberStyle::Arabic,
+        }
+    }
+
+    // letters and roman numerals have no zero, roman numerals end at 3999, arabic numbers are used instead
+    pub(crate) fn format(self, number: usize) -> String {
+        match self {
+            NumberStyle::Alpha if number > 0 => alpha_number(number),
+            NumberStyle::Roman if number > 0 && number < 4000 => roman_number(number),
+            _ => number.to_string(),
+        }
+    }
+}
+
+// a, b, ..., z, aa, ab, ...
+pub(crate) fn alpha_number(mut number: usize) -> String {
+    let mut letters = Vec::new();
+
+    while number > 0 {
+        number -= 1;
+        letters.push(char::from(b'a' + (number % 26) as u8));
+        number /= 26;
+    }
+
+    letters.into_iter().rev().collect()
+}
+
+// i, ii, iii, iv, ...
+pub(crate) fn roman_number(mut number: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut roman = String::new();
+
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+
+    roman
+}
+
+// numbers put in front of the output values
+pub(crate) struct Numbering {
+    pub(crate) start: usize,
+    pub(crate) pad: usize,
+    pub(crate) style: NumberStyle,
+    pub(crate) separator: String,
+}
+
+impl Numbering {
+    pub(crate) fn apply(&self, index: usize, value: String) -> String {
+        let number = self.style.format(self.start + index);
+
+        // arabic numbers are padded with zeros, letters and roman numerals are aligned to the right
+        if self.style == NumberStyle::Arabic {
+            format!(
+                "{:0>pad$}{}{}",
+                number,
+                self.separator,
+                value,
+                pad = self.pad
+            )
+        } else {
+            format!(
+                "{:>pad$}{}{}",
+                number,
+                self.separator,
+                value,
+                pad = self.pad
+            )
+        }
+    }
+}
 
-#[allow(clippy::assigning_clones)]
-pub(crate) fn create_block<'rc>(param: &PathAndJson<'rc>) -> BlockContext<'rc> {
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Base64,
+    Url,
+}
+
+impl Encoding {
+    pub(crate) fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "base64" => Some(Encoding::Base64),
+            "url" => Some(Encoding::Url),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn apply(self, value: String) -> String {
+        match self {
+            Encoding::Base64 => base64_encode(value.as_bytes()),
+            Encoding::Url => percent_encode(&value),
+        }
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// standard base64 alphabet with padding, as in RFC 4648
+pub(crate) fn base64_encode(input: &[u8]) -> String {
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | u32::from(*byte) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+// percent-encode everything but the unreserved characters of RFC 3986, so each value is safe as a path segment or query component
+pub(crate) fn percent_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+// borrow array items and object values through their context path, cloning only derived values
+pub(crate) fn create_item_block<'rc>(
+    param: &PathAndJson<'rc>,
+    segments: Vec<String>,
+    value: &serde_json::Value,
+) -> BlockContext<'rc> {
     let mut block = BlockContext::new();
 
-    if let Some(new_path) = param.context_path() {
-        *block.base_path_mut() = new_path.clone();
+    if let Some(path) = param.context_path() {
+        let base_path = block.base_path_mut();
+        base_path.extend(path.iter().cloned());
+        base_path.extend(segments);
     } else {
-        // use clone for now
-        block.set_base_value(param.value().clone());
+        block.set_base_value(value.clone());
     }
 
     block
 }
 
-pub(crate) fn apply_wrapper(subject: String, wrapper: &str, wrap: bool) -> String {
+// the current context as `this` would resolve in the template, used when the helper is called without params
+pub(crate) fn current_context<'rc>(
+    ctx: &'rc Context,
+    rc: &RenderContext<'_, 'rc>,
+) -> PathAndJson<'rc> {
+    let value = match rc.block() {
+        Some(block) => match block.base_value() {
+            Some(value) => ScopedJson::Derived(value.clone()),
+            None => block
+                .base_path()
+                .iter()
+                .try_fold(ctx.data(), |value, segment| match value {
+                    serde_json::Value::Object(o) => o.get(segment),
+                    serde_json::Value::Array(ar) => segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| ar.get(index)),
+                    _ => None,
+                })
+                .map(|value| ScopedJson::Context(value, block.base_path().clone()))
+                .unwrap_or(ScopedJson::Missing),
+        },
+        None => ScopedJson::Context(ctx.data(), Vec::new()),
+    };
+
+    PathAndJson::new(Some("this".to_string()), value)
+}
+
+// split a JSON pointer into the path segments it descends through, e.g. "/a~1b/0" into ["a/b", "0"]
+pub(crate) fn pointer_segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+// expand nested arrays up to specific depth, keeping the path of each item relative to the top level array
+pub(crate) fn flatten_items(
+    ar: &[serde_json::Value],
+    depth: usize,
+) -> Vec<(Vec<String>, &serde_json::Value)> {
+    let mut items = Vec::with_capacity(ar.len());
+
+    for (index, item) in ar.iter().enumerate() {
+        match item {
+            serde_json::Value::Array(inner) if depth > 0 => {
+                items.extend(flatten_items(inner, depth - 1).into_iter().map(
+                    |(mut path, item)| {
+                        path.insert(0, index.to_string());
+                        (path, item)
+                    },
+                ));
+            }
+            _ => items.push((vec![index.to_string()], item)),
+        }
+    }
+
+    items
+}
+
+// walk nested arrays and objects, collecting scalar values only
+pub(crate) fn collect_leaves<'a>(
+    value: &'a serde_json::Value,
+    leaves: &mut Vec<&'a serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Array(ar) => ar.iter().for_each(|item| collect_leaves(item, leaves)),
+        serde_json::Value::Object(o) => o.values().for_each(|item| collect_leaves(item, leaves)),
+        _ => leaves.push(value),
+    }
+}
+
+// render the block template, or the inline item template in its absence, within specific block context
+pub(crate) fn render_block<'reg: 'rc, 'rc>(
+    template: Option<&'rc Template>,
+    item_template: Option<&Template>,
+    block: BlockContext<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> Result<(), RenderError> {
+    match (template, item_template) {
+        (Some(template), _) => {
+            rc.push_block(block);
+            template.render(r, ctx, rc, out)?;
+            rc.pop_block();
+        }
+        (None, Some(item_template)) => {
+            // the item template does not live as long as the render context, use a short lived copy of it
+            let mut item_rc: RenderContext<'reg, '_> = rc.clone();
+            item_rc.push_block(block);
+            item_template.render(r, ctx, &mut item_rc, out)?;
+        }
+        (None, None) => {}
+    }
+
+    Ok(())
+}
+
+// block render error of the param at specific index
+pub(crate) fn render_failed(
+    values: &[PathAndJson<'_>],
+    index: usize,
+    source: RenderError,
+) -> RenderError {
+    ConcatError::RenderFailed {
+        index,
+        path: values[index].relative_path().cloned(),
+        source,
+    }
+    .into()
+}
+
+// growable buffer shared by the values rendered through the block template, keeping its capacity between them
+#[derive(Default)]
+pub(crate) struct BlockOutput {
+    buf: String,
+}
+
+impl Output for BlockOutput {
+    fn write(&mut self, seg: &str) -> Result<(), std::io::Error> {
+        self.buf.push_str(seg);
+        Ok(())
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> Result<(), std::io::Error> {
+        std::fmt::Write::write_fmt(&mut self.buf, args).map_err(std::io::Error::other)
+    }
+}
+
+impl BlockOutput {
+    // copy the rendered value out at its exact size and clear the buffer for the next one
+    pub(crate) fn take(&mut self) -> String {
+        let value = self.buf.as_str().to_owned();
+        self.buf.clear();
+        value
+    }
+}
+
+// templates without partials and decorators, which rely on render state beyond the enclosing blocks
+#[cfg(all(feature = "rayon", feature = "handlebars6"))]
+pub(crate) fn is_isolated_template(template: &Template) -> bool {
+    use handlebars::template::TemplateElement;
+
+    template.elements.iter().all(|element| match element {
+        TemplateElement::HelperBlock(helper) => {
+            helper.template.as_ref().is_none_or(is_isolated_template)
+                && helper.inverse.as_ref().is_none_or(is_isolated_template)
+        }
+        TemplateElement::DecoratorExpression(_)
+        | TemplateElement::DecoratorBlock(_)
+        | TemplateElement::PartialExpression(_)
+        | TemplateElement::PartialBlock(_) => false,
+        _ => true,
+    })
+}
+
+// render blocks on the rayon thread pool, each one in its own render context carrying the enclosing blocks
+#[cfg(all(feature = "rayon", feature = "handlebars6"))]
+pub(crate) fn render_parallel<'reg: 'rc, 'rc>(
+    pending: Vec<PendingValue<'rc>>,
+    values: &[PathAndJson<'rc>],
+    template: Option<&'rc Template>,
+    item_template: Option<&Template>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+) -> Result<Vec<PendingValue<'rc>>, RenderError> {
+    use rayon::prelude::*;
+
+    let blocks = rc.replace_blocks(std::collections::VecDeque::new());
+    rc.replace_blocks(blocks.clone());
+
+    let root_template = rc.get_root_template_name();
+    let current_template = rc.get_current_template_name();
+    let disable_escape = rc.is_disable_escape();
+
+    pending
+        .into_par_iter()
+        .map_init(BlockOutput::default, |content, value| match value {
+            PendingValue::Block(block, index) => {
+                let mut item_rc = RenderContext::new(root_template);
+                item_rc.replace_blocks(blocks.clone());
+                item_rc.set_current_template_name(current_template);
+                item_rc.set_disable_escape(disable_escape);
+                item_rc.set_recursive_lookup(r.recursive_lookup());
+
+                render_block(
+                    template,
+                    item_template,
+                    *block,
+                    r,
+                    ctx,
+                    &mut item_rc,
+                    content,
+                )
+                .map_err(|source| render_failed(values, index, source))?;
+
+                Ok(PendingValue::Rendered(content.take()))
+            }
+            value => Ok(value),
+        })
+        .collect()
+}
+
+// pick the values to render from an array item or object value, when there is no block template
+pub(crate) fn expand_item<'a>(
+    item: &'a serde_json::Value,
+    pluck: Option<&str>,
+    recursive: bool,
+    leaves: &mut Vec<&'a serde_json::Value>,
+) {
+    let item = match pluck {
+        Some(field) => match lookup_field(item, field) {
+            Some(item) => item,
+            None => return,
+        },
+        None => item,
+    };
+
+    if recursive {
+        collect_leaves(item, leaves);
+    } else {
+        leaves.push(item);
+    }
+}
+
+// object as local variable along with each of its fields, e.g. `@extra` and `@extra_class`, as local variables can't be navigated into
+pub(crate) fn set_object_locals(
+    block: &mut BlockContext<'_>,
+    name: &str,
+    value: serde_json::Value,
+) {
+    if let serde_json::Value::Object(fields) = &value {
+        for (field, field_value) in fields {
+            block.set_local_var(&format!("{}_{}", name, field), field_value.clone());
+        }
+    }
+    block.set_local_var(name, value);
+}
+
+pub(crate) fn set_block_locals(block: &mut BlockContext<'_>, index: usize, len: usize) {
+    block.set_local_var("index", serde_json::Value::from(index));
+    block.set_local_var("first", serde_json::Value::from(index == 0));
+    block.set_local_var("last", serde_json::Value::from(index + 1 == len));
+}
+
+// templates referring to `@prev` or `@next`, or rendering partials and decorators which might
+pub(crate) fn uses_adjacent_locals(template: &Template) -> bool {
+    template.elements.iter().any(element_uses_adjacent_locals)
+}
+
+fn element_uses_adjacent_locals(element: &handlebars::template::TemplateElement) -> bool {
+    use handlebars::template::TemplateElement;
+
+    match element {
+        TemplateElement::Expression(helper)
+        | TemplateElement::HtmlExpression(helper)
+        | TemplateElement::HelperBlock(helper) => {
+            param_uses_adjacent_locals(&helper.name)
+                || helper.params.iter().any(param_uses_adjacent_locals)
+                || helper.hash.values().any(param_uses_adjacent_locals)
+                || helper.template.as_ref().is_some_and(uses_adjacent_locals)
+                || helper.inverse.as_ref().is_some_and(uses_adjacent_locals)
+        }
+        TemplateElement::DecoratorExpression(_)
+        | TemplateElement::DecoratorBlock(_)
+        | TemplateElement::PartialExpression(_)
+        | TemplateElement::PartialBlock(_) => true,
+        _ => false,
+    }
+}
+
+fn param_uses_adjacent_locals(param: &handlebars::template::Parameter) -> bool {
+    use handlebars::template::Parameter;
+
+    match param {
+        Parameter::Path(handlebars::Path::Local((_, name, _))) => {
+            ["prev", "next"].iter().any(|adjacent| {
+                name.strip_prefix(adjacent)
+                    .is_some_and(|field| field.is_empty() || field.starts_with('_'))
+            })
+        }
+        Parameter::Subexpression(subexpression) => {
+            element_uses_adjacent_locals(&subexpression.element)
+        }
+        _ => false,
+    }
+}
+
+// raw values of the adjacent array items or object values, left unset at the boundaries
+pub(crate) fn set_adjacent_locals(
+    block: &mut BlockContext<'_>,
+    prev: Option<&serde_json::Value>,
+    next: Option<&serde_json::Value>,
+) {
+    if let Some(prev) = prev {
+        set_object_locals(block, "prev", prev.clone());
+    }
+    if let Some(next) = next {
+        set_object_locals(block, "next", next.clone());
+    }
+}
+
+// value wrapped in opening and closing quotation marks, the same ones unless set otherwise
+pub(crate) fn apply_wrapper(
+    subject: String,
+    (open, close): (&str, &str),
+    wrap: bool,
+    escape: QuoteEscape,
+) -> String {
     if wrap {
-        format!("{}{}{}", wrapper, subject, wrapper)
+        let subject = if close.is_empty() {
+            subject
+        } else {
+            match escape {
+                QuoteEscape::Backslash => {
+                    let subject = subject
+                        .replace('\\', "\\\\")
+                        .replace(close, &format!("\\{}", close));
+                    if open.is_empty() || open == close {
+                        subject
+                    } else {
+                        subject.replace(open, &format!("\\{}", open))
+                    }
+                }
+                // doubling the closing one, like SQL identifiers
+                QuoteEscape::Double => subject.replace(close, &format!("{}{}", close, close)),
+                QuoteEscape::None => subject,
+            }
+        };
+
+        format!("{}{}{}", open, subject, close)
     } else {
         subject
     }
 }
 
-#[derive(Clone, Copy)]
-/// Concat helper for handlebars-rust
-///
-/// # Registration
-///
-/// ```rust
-/// use handlebars::Handlebars;
-/// use handlebars_concat::HandlebarsConcat;
-/// use serde_json::json;
-///
-/// let mut h = Handlebars::new();
-/// h.register_helper("concat", Box::new(HandlebarsConcat));
-///
-/// assert_eq!(h.render_template(r#"{{concat item1 item2}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "Value 1,Value 2");
-/// assert_eq!(h.render_template(r#"{{concat this separator=", "}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "item1, item2");
-/// assert_eq!(h.render_template(r#"{{#concat this separator=", "}}{{this}}{{/concat}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "Value 1, Value 2");
-/// assert_eq!(h.render_template(r#"{{#concat "Form" this separator="" render_all=true}}<{{#if tag}}{{tag}}{{else}}{{this}}{{/if}}/>{{/concat}}"#, &json!({"key0":{"tag":"Input"},"key1":{"tag":"Select"},"key2":{"tag":"Button"}})).expect("Render error"), "<Form/><Input/><Select/><Button/>");
-/// ```
-///
-/// # Behavior
-///
-/// The helper is looking for multiple arguments of type string, array or object. Arguments are being added to an output buffer and returned altogether as string.
-///
-/// The helper has few parameters modifying the behavior slightly. For example `distinct=true` eliminates duplicate values from the output buffer, while `quotes=true` in combination with `single_quote=true` wraps the values in quotation marks.
-///
-/// ## String
-/// ~~String arguments are added directly to the output buffer.~~
-/// As of `0.1.3` strings could be handled in one of two ways:
-/// 1. By default strings are added to the output buffer without modification (other than the quotation mark modifiers).
-/// 2. If you add a block template and use the `render_all` parameter, strings will be passed as `{{this}}` to the block template.
-///
-/// The block template rendering is disabled by default for backward compatibility.
-///
-/// ## Array
-/// ~~Array arguments are iterated and added as individual strings to the output buffer.~~
-/// As of `0.1.3` arrays could be handled in one of two ways:
-/// 1. By default array values are added as individual strings to the output buffer without modification (other than the quotation mark modifiers).
-/// 2. If you add a block template and use the `render_all` parameter, array values are passed as `{{this}}` to the block template.
-///
-/// The block template rendering is disabled by default for backward compatibility.
-///
-/// ## Object
-/// Object arguments could be handled two different ways:
-/// 1. By default only the object keys are being used and the values are ignored.
-/// 2. If you add a block template the helper will use it to render the object value and
-/// concatenate it as string to the output buffer.
-///
-/// Object rendering results are subject to `distinct`, `quotes` and `single_quote` modifier parameters, just like strings and arrays.
-///
-/// # Hash parameters
-///
-/// * separator: Set specific string to join elements with. Default is ","
-/// * distinct: Eliminate duplicates upon adding to output buffer
-/// * quotes: Wrap each value in double quotation marks
-/// * single_quote: Modifier of `quotes` to switch to single quotation mark instead
-/// * render_all: Render all values using the block template, not just object values
-///
-/// # Example usage:
-///
-///
-/// Example with string literals:
-///
-/// ```handlebars
-/// {{concat "One" "Two" separator=", "}}
-/// ```
-///
-/// Result: `One, Two`
-///
-/// ---
-///
-/// ```handlebars
-/// {{concat "One" "Two" separator=", " quotes=true}}
-/// ```
-///
-/// Result: `"One", "Two"`
-///
-/// ---
-///
-/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"Three":3}`
-///
-/// ```handlebars
-/// {{concat s arr obj separator=", " distinct=true}}
-/// ```
-///
-/// Result: `One, Two, Three`
-///
-/// ---
-///
-/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}`:
-///
-/// ```handlebars
-/// {{#concat s arr obj separator=", " distinct=true}}{{label}}{{/concat}}
-/// ```
-///
-/// Result: `One, Two, Three, Four`
-///
-/// ---
-///
-/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}`
-///
-/// ```handlebars
-/// {{#concat s arr obj separator=", " distinct=true render_all=true}}<{{#if label}}{{label}}{{else}}{{this}}{{/if}}/>{{/concat}}
-/// ```
-///
-/// Result: `<One/>, <Two/>, <Three/>, <Four/>`
-///
-/// ---
-///
-pub struct HandlebarsConcat;
+// SQL identifier wrapped in specific delimiters, doubling the closing delimiter inside of it, e.g. `` `a``b` `` or `[a]]b]`
+pub(crate) fn quote_identifier(subject: &str, open: &str, close: &str) -> String {
+    format!(
+        "{}{}{}",
+        open,
+        subject.replace(close, &format!("{}{}", close, close)),
+        close
+    )
+}
 
-impl HelperDef for HandlebarsConcat {
-    fn call<'reg: 'rc, 'rc>(
-        &self,
-        h: &Helper<'rc>,
-        r: &'reg Handlebars,
-        ctx: &'rc Context,
-        rc: &mut RenderContext<'reg, 'rc>,
-        out: &mut dyn Output,
-    ) -> HelperResult {
-        let separator = if let Some(s) = h.hash_get("separator") {
-            s.render()
+// JSON string literal of the value, also escaping the line and paragraph separators JavaScript did not accept in string literals
+pub(crate) fn json_string(subject: &str) -> String {
+    serde_json::Value::from(subject)
+        .to_string()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+// cut values longer than specific number of grapheme clusters, appending the ellipsis,
+// so emoji and combining characters are never split apart
+pub(crate) fn truncate(subject: String, max_length: usize, ellipsis: &str) -> String {
+    match subject.grapheme_indices(true).nth(max_length) {
+        Some((end, _)) => format!("{}{}", &subject[..end], ellipsis),
+        None => subject,
+    }
+}
+
+// pad values narrower than specific display width at the start and the end,
+// wide CJK characters and emoji taking up two columns, the same as in a terminal
+pub(crate) fn apply_padding(
+    subject: String,
+    pad_start: usize,
+    pad_end: usize,
+    pad_char: char,
+) -> String {
+    let width = subject.width();
+
+    if width >= pad_start && width >= pad_end {
+        return subject;
+    }
+
+    let start = pad_start.saturating_sub(width);
+    let end = pad_end.saturating_sub(width + start);
+
+    let mut padded = String::with_capacity(subject.len() + (start + end) * pad_char.len_utf8());
+    push_padding(&mut padded, start, pad_char);
+    padded.push_str(&subject);
+    push_padding(&mut padded, end, pad_char);
+    padded
+}
+
+// fill specific number of columns with the pad character, completing with spaces where a wide one does not fit
+fn push_padding(padded: &mut String, columns: usize, pad_char: char) {
+    let pad_width = pad_char.width().unwrap_or_default().max(1);
+
+    padded.extend(std::iter::repeat_n(pad_char, columns / pad_width));
+    padded.extend(std::iter::repeat_n(' ', columns % pad_width));
+}
+
+pub(crate) fn apply_affixes(subject: String, prefix: &str, suffix: &str) -> String {
+    if prefix.is_empty() && suffix.is_empty() {
+        subject
+    } else {
+        format!("{}{}{}", prefix, subject, suffix)
+    }
+}
+
+// prefix each line of a value, leaving empty lines as they are
+pub(crate) fn indent_lines(subject: String, indent: &str) -> String {
+    if indent.is_empty() {
+        return subject;
+    }
+
+    let mut indented = String::with_capacity(subject.len() + indent.len());
+
+    for line in subject.split_inclusive('\n') {
+        if !line.trim_end_matches(['\r', '\n']).is_empty() {
+            indented.push_str(indent);
+        }
+        indented.push_str(line);
+    }
+
+    indented
+}
+
+// boolean hash parameter of helpers without registration time defaults, anything but `false` and `null` enables it
+pub(crate) fn hash_flag(h: &Helper<'_>, name: &str) -> bool {
+    h.hash_get(name).is_some_and(|param| {
+        !matches!(
+            param.value(),
+            serde_json::Value::Null | serde_json::Value::Bool(false)
+        )
+    })
+}
+
+// hash parameters forwarded to the block template as `@extra`, regardless of their name
+pub(crate) const EXTRA_PREFIX: &str = "ctx_";
+
+// unrecognized hash parameters and the ones with the dedicated prefix, which is stripped
+pub(crate) fn extra_hash(h: &Helper<'_>) -> serde_json::Map<String, serde_json::Value> {
+    h.hash()
+        .iter()
+        .filter_map(|(name, value)| match name.strip_prefix(EXTRA_PREFIX) {
+            Some(name) => Some((name.to_string(), value.value().clone())),
+            None if !HASH_PARAMS.contains(name) => Some((name.to_string(), value.value().clone())),
+            None => None,
+        })
+        .collect()
+}
+
+pub(crate) fn validate_hash(h: &Helper<'_>) -> Result<(), RenderError> {
+    let unknown = h
+        .hash()
+        .keys()
+        .filter(|name| !HASH_PARAMS.contains(name) && !name.starts_with(EXTRA_PREFIX))
+        .map(|name| name.to_string())
+        .collect::<Vec<String>>();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(ConcatError::UnknownHashParams(unknown).into())
+    }
+}
+
+pub(crate) fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok()
+}
+
+// filters applied to each value before eliminating duplicates
+pub(crate) enum ValueFilter {
+    Contains(String),
+    StartsWith(String),
+    EndsWith(String),
+    GreaterThan(f64),
+    GreaterOrEqual(f64),
+    LessThan(f64),
+    LessOrEqual(f64),
+    #[cfg(feature = "regex")]
+    Match(regex::Regex),
+    #[cfg(feature = "regex")]
+    ExcludeMatch(regex::Regex),
+}
+
+impl ValueFilter {
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        match *self {
+            ValueFilter::Contains(ref needle) => value.contains(needle.as_str()),
+            ValueFilter::StartsWith(ref needle) => value.starts_with(needle.as_str()),
+            ValueFilter::EndsWith(ref needle) => value.ends_with(needle.as_str()),
+            ValueFilter::GreaterThan(bound) => parse_number(value).is_some_and(|n| n > bound),
+            ValueFilter::GreaterOrEqual(bound) => parse_number(value).is_some_and(|n| n >= bound),
+            ValueFilter::LessThan(bound) => parse_number(value).is_some_and(|n| n < bound),
+            ValueFilter::LessOrEqual(bound) => parse_number(value).is_some_and(|n| n <= bound),
+            #[cfg(feature = "regex")]
+            ValueFilter::Match(ref pattern) => pattern.is_match(value),
+            #[cfg(feature = "regex")]
+            ValueFilter::ExcludeMatch(ref pattern) => !pattern.is_match(value),
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+pub(crate) fn compile_pattern(
+    name: &'static str,
+    pattern: &str,
+) -> Result<regex::Regex, RenderError> {
+    regex::Regex::new(pattern).map_err(|e| {
+        ConcatError::InvalidHashParam {
+            name,
+            value: pattern.to_string(),
+            reason: e.to_string(),
+        }
+        .into()
+    })
+}
+
+// Rhai script evaluated against the output values, compiled once per helper call
+#[cfg(feature = "rhai")]
+pub(crate) struct Script {
+    name: &'static str,
+    engine: rhai::Engine,
+    ast: rhai::AST,
+}
+
+#[cfg(feature = "rhai")]
+impl Script {
+    pub(crate) fn compile(name: &'static str, source: &str) -> Result<Self, RenderError> {
+        let engine = rhai::Engine::new();
+        let ast = engine
+            .compile(source)
+            .map_err(|e| ConcatError::InvalidHashParam {
+                name,
+                value: source.to_string(),
+                reason: e.to_string(),
+            })?;
+
+        Ok(Script { name, engine, ast })
+    }
+
+    fn eval<T: Clone + 'static>(&self, scope: &mut rhai::Scope) -> Result<T, RenderError> {
+        self.engine
+            .eval_ast_with_scope::<T>(scope, &self.ast)
+            .map_err(|e| {
+                ConcatError::ScriptFailed {
+                    name: self.name,
+                    reason: e.to_string(),
+                }
+                .into()
+            })
+    }
+
+    // keep the values for which the script evaluates to `true`, exposing each one as `value`
+    pub(crate) fn filter<T: AsRef<str>>(&self, output: Vec<T>) -> Result<Vec<T>, RenderError> {
+        let mut filtered = Vec::with_capacity(output.len());
+
+        for value in output {
+            let mut scope = rhai::Scope::new();
+            scope.push("value", value.as_ref().to_string());
+
+            if self.eval::<bool>(&mut scope)? {
+                filtered.push(value);
+            }
+        }
+
+        Ok(filtered)
+    }
+
+    // compare pairs of values exposed as `a` and `b`, a negative result puts `a` first
+    pub(crate) fn sort<T: AsRef<str>>(&self, output: &mut [T]) -> Result<(), RenderError> {
+        let mut error = None;
+
+        output.sort_by(|a, b| {
+            if error.is_some() {
+                return Ordering::Equal;
+            }
+
+            let mut scope = rhai::Scope::new();
+            scope.push("a", a.as_ref().to_string());
+            scope.push("b", b.as_ref().to_string());
+
+            match self.eval::<rhai::INT>(&mut scope) {
+                Ok(result) => result.cmp(&0),
+                Err(e) => {
+                    error = Some(e);
+                    Ordering::Equal
+                }
+            }
+        });
+
+        error.map_or(Ok(()), Err)
+    }
+}
+
+// rendering of scalar values outside of the block template
+#[derive(Default)]
+pub(crate) struct ScalarFormat {
+    pub(crate) null: Option<String>,
+    pub(crate) bool_true: Option<String>,
+    pub(crate) bool_false: Option<String>,
+    pub(crate) precision: Option<usize>,
+    pub(crate) thousands_separator: Option<String>,
+    pub(crate) leading_zeros: Option<usize>,
+}
+
+impl ScalarFormat {
+    pub(crate) fn render(&self, value: &serde_json::Value) -> Option<String> {
+        match value {
+            serde_json::Value::Null => self.null.clone(),
+            serde_json::Value::Bool(true) if self.bool_true.is_some() => self.bool_true.clone(),
+            serde_json::Value::Bool(false) if self.bool_false.is_some() => self.bool_false.clone(),
+            serde_json::Value::Number(number) => Some(self.format_number(number)),
+            _ => Some(value.render()),
+        }
+    }
+
+    pub(crate) fn format_number(&self, number: &serde_json::Number) -> String {
+        let formatted = match (self.precision, number.as_f64()) {
+            (Some(precision), Some(float)) => format!("{:.*}", precision, float),
+            _ => number.to_string(),
+        };
+
+        if (self.thousands_separator.is_none() && self.leading_zeros.is_none())
+            || formatted.contains(['e', 'E'])
+        {
+            return formatted;
+        }
+
+        let (sign, unsigned) = match formatted.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", formatted.as_str()),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut integer = integer.to_string();
+        if let Some(leading_zeros) = self.leading_zeros {
+            while integer.len() < leading_zeros {
+                integer.insert(0, '0');
+            }
+        }
+
+        if let Some(thousands_separator) = &self.thousands_separator {
+            let digits = integer.chars().collect::<Vec<char>>();
+            integer = digits
+                .rchunks(3)
+                .rev()
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<String>>()
+                .join(thousands_separator);
+        }
+
+        match fraction {
+            Some(fraction) => format!("{}{}.{}", sign, integer, fraction),
+            None => format!("{}{}", sign, integer),
+        }
+    }
+}
+
+// format string applied to values outside of the block template, e.g. "[{value}]"
+#[derive(Default)]
+pub(crate) struct ItemFormat(pub(crate) Option<String>);
+
+impl ItemFormat {
+    // substitute the placeholders in a single pass, so placeholders within the value itself are kept as they are
+    pub(crate) fn apply(&self, value: String, index: usize, key: Option<&str>) -> String {
+        let Some(format) = &self.0 else {
+            return value;
+        };
+
+        let mut formatted = String::with_capacity(format.len() + value.len());
+        let mut rest = format.as_str();
+
+        while let Some(start) = rest.find('{') {
+            formatted.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(tail) = rest.strip_prefix("{value}") {
+                formatted.push_str(&value);
+                rest = tail;
+            } else if let Some(tail) = rest.strip_prefix("{index}") {
+                formatted.push_str(&index.to_string());
+                rest = tail;
+            } else if let Some(tail) = rest.strip_prefix("{key}") {
+                formatted.push_str(key.unwrap_or_default());
+                rest = tail;
+            } else {
+                formatted.push('{');
+                rest = &rest[1..];
+            }
+        }
+
+        formatted.push_str(rest);
+        formatted
+    }
+}
+
+// lowercase letters and digits, joining the words with dashes and dropping anything else
+pub(crate) fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    let mut dash = false;
+
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            if dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            dash = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' || c == '_' {
+            dash = true;
+        }
+    }
+
+    slug
+}
+
+// value to add to the output buffer, either rendered already or yet to be rendered through the block template,
+// along with the index of the param it comes from, or block template output rendered ahead of time
+pub(crate) enum PendingValue<'rc> {
+    // rendered value, along with whether it comes from a JSON number, boolean or null rather than a string
+    Value(String, bool),
+    // rendered array item or object key or value, kept even when empty
+    Item(String, bool),
+    Block(Box<BlockContext<'rc>>, usize),
+    #[cfg(all(feature = "rayon", feature = "handlebars6"))]
+    Rendered(String),
+}
+
+// buffered value, along with whether it comes from a JSON number, boolean or null rather than a string
+pub(crate) struct OutputValue {
+    pub(crate) value: String,
+    pub(crate) literal: bool,
+}
+
+impl From<String> for OutputValue {
+    fn from(value: String) -> Self {
+        OutputValue {
+            value,
+            literal: false,
+        }
+    }
+}
+
+impl AsRef<str> for OutputValue {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+// insertion ordered buffer, tracking already seen values when eliminating duplicates
+#[derive(Default)]
+pub(crate) struct OutputBuffer {
+    values: Vec<Option<OutputValue>>,
+    counts: Vec<usize>,
+    positions: HashMap<String, usize>,
+    seen_fields: HashSet<String>,
+    pub(crate) distinct: Option<DistinctMode>,
+    pub(crate) keep_last: bool,
+    pub(crate) count_duplicates: bool,
+    pub(crate) frequency: Option<String>,
+    pub(crate) trim: bool,
+    pub(crate) trim_block: bool,
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) normalize: bool,
+    pub(crate) case: Option<CaseMode>,
+    pub(crate) slugify: bool,
+    pub(crate) keep_empty: bool,
+    pub(crate) filters: Vec<ValueFilter>,
+}
+
+impl OutputBuffer {
+    // block template output, trimmed and skipped when nothing but whitespace is left with `trim_block`
+    pub(crate) fn push_block(&mut self, value: String) {
+        if !self.trim_block {
+            return self.push(value, false);
+        }
+
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            self.push(trimmed.to_string(), false);
+        }
+    }
+
+    // array items and object keys and values, skipped when empty only if `trim` leaves nothing of them
+    pub(crate) fn push_item(&mut self, value: String, literal: bool) {
+        self.push_value(value, literal, self.keep_empty || !self.trim);
+    }
+
+    pub(crate) fn push(&mut self, value: String, literal: bool) {
+        self.push_value(value, literal, self.keep_empty);
+    }
+
+    fn push_value(&mut self, value: String, literal: bool, keep_empty: bool) {
+        let value = if self.trim {
+            value.trim().to_string()
         } else {
-            ",".to_string()
+            value
+        };
+
+        #[cfg(feature = "unicode-normalization")]
+        let value = if self.normalize && !unicode_normalization::is_nfc(&value) {
+            unicode_normalization::UnicodeNormalization::nfc(value.as_str()).collect()
+        } else {
+            value
+        };
+
+        let value = match self.case {
+            Some(case) => case.apply(value),
+            None => value,
+        };
+
+        let value = if self.slugify { slugify(&value) } else { value };
+
+        if value.is_empty() && !keep_empty {
+            return;
+        }
+
+        if !self.filters.iter().all(|filter| filter.matches(&value)) {
+            return;
+        }
+
+        let value = OutputValue { value, literal };
+
+        let key = match self.distinct {
+            None => {
+                self.values.push(Some(value));
+                self.counts.push(1);
+                return;
+            }
+            Some(DistinctMode::CaseSensitive) => value.value.clone(),
+            Some(DistinctMode::CaseInsensitive) => value.value.to_lowercase(),
         };
 
-        // filter output
-        let distinct = h.hash_get("distinct").is_some();
+        match self.positions.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(self.values.len());
+                self.values.push(Some(value));
+                self.counts.push(1);
+            }
+            Entry::Occupied(mut entry) if self.keep_last => {
+                // drop the earlier occurrence, leaving a gap in its place
+                let earlier = entry.insert(self.values.len());
+                self.values[earlier] = None;
+                self.values.push(Some(value));
+                self.counts.push(self.counts[earlier] + 1);
+            }
+            Entry::Occupied(entry) => {
+                self.counts[*entry.get()] += 1;
+            }
+        }
+    }
+
+    // check the field of a source value against the ones already seen, values without the field always pass
+    pub(crate) fn is_distinct_field(&mut self, value: &serde_json::Value, field: &str) -> bool {
+        lookup_field(value, field)
+            .map(|key| self.seen_fields.insert(key.render()))
+            .unwrap_or(true)
+    }
+
+    pub(crate) fn into_values(self) -> Vec<OutputValue> {
+        if let Some(pair_separator) = &self.frequency {
+            let mut counted = self
+                .values
+                .into_iter()
+                .zip(self.counts)
+                .filter_map(|(value, count)| value.map(|value| (value, count)))
+                .collect::<Vec<_>>();
+
+            // most frequent first, ties keep their order of appearance
+            counted.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+            return counted
+                .into_iter()
+                .map(|(value, count)| {
+                    OutputValue::from(format!("{}{}{}", value.value, pair_separator, count))
+                })
+                .collect();
+        }
+
+        if !self.count_duplicates {
+            return self.values.into_iter().flatten().collect();
+        }
+
+        self.values
+            .into_iter()
+            .zip(self.counts)
+            .filter_map(|(value, count)| match (value, count) {
+                (Some(value), 1) => Some(value),
+                (Some(value), count) => {
+                    Some(OutputValue::from(format!("{} (x{})", value.value, count)))
+                }
+                (None, _) => None,
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn sort_output<T: AsRef<str>>(output: &mut [T], mode: SortMode) {
+    match mode {
+        SortMode::Ascending => output.sort_by(|a, b| a.as_ref().cmp(b.as_ref())),
+        SortMode::Descending => output.sort_by(|a, b| b.as_ref().cmp(a.as_ref())),
+        SortMode::Numeric => output.sort_by(|a, b| compare_numeric(a.as_ref(), b.as_ref())),
+        SortMode::Natural => output.sort_by(|a, b| compare_natural(a.as_ref(), b.as_ref())),
+    }
+}
+
+#[cfg(feature = "icu")]
+pub(crate) fn create_collator(locale: &str) -> Result<icu_collator::Collator, RenderError> {
+    let invalid = |reason: String| ConcatError::InvalidHashParam {
+        name: "locale",
+        value: locale.to_string(),
+        reason,
+    };
+
+    let parsed = locale
+        .parse::<icu_locid::Locale>()
+        .map_err(|e| invalid(e.to_string()))?;
+
+    icu_collator::Collator::try_new(&(&parsed).into(), icu_collator::CollatorOptions::new())
+        .map_err(|e| invalid(format!("unsupported locale, {}", e)).into())
+}
+
+// locale aware variant of the lexicographic sort modes
+#[cfg(feature = "icu")]
+pub(crate) fn sort_collated<T: AsRef<str>>(
+    output: &mut [T],
+    mode: SortMode,
+    collator: &icu_collator::Collator,
+) {
+    match mode {
+        SortMode::Ascending => output.sort_by(|a, b| collator.compare(a.as_ref(), b.as_ref())),
+        SortMode::Descending => output.sort_by(|a, b| collator.compare(b.as_ref(), a.as_ref())),
+        _ => sort_output(output, mode),
+    }
+}
+
+// numbers come first in ascending order, anything else keeps its relative position after them
+pub(crate) fn compare_numeric(a: &str, b: &str) -> Ordering {
+    // "NaN" parses, but is not a number to order by
+    let parse = |value: &str| value.trim().parse::<f64>().ok().filter(|n| !n.is_nan());
+
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// compare runs of digits by their numeric value and anything else lexicographically, e.g. "item2" before "item10"
+pub(crate) fn compare_natural(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+
+                // longer run of significant digits is the larger number
+                let ordering = x
+                    .trim_start_matches('0')
+                    .len()
+                    .cmp(&y.trim_start_matches('0').len())
+                    .then_with(|| x.trim_start_matches('0').cmp(y.trim_start_matches('0')))
+                    .then_with(|| x.len().cmp(&y.len()));
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+
+    digits
+}
+
+pub(crate) fn lookup_field<'a>(
+    value: &'a serde_json::Value,
+    field: &str,
+) -> Option<&'a serde_json::Value> {
+    field
+        .split('.')
+        .try_fold(value, |value, key| match value {
+            serde_json::Value::Array(ar) => key.parse::<usize>().ok().and_then(|i| ar.get(i)),
+            _ => value.get(key),
+        })
+        .filter(|value| !value.is_null())
+}
+
+pub(crate) fn matches_field(value: &serde_json::Value, field: &str, expected: &str) -> bool {
+    lookup_field(value, field).is_some_and(|value| value.render() == expected)
+}
+
+pub(crate) fn compare_fields(
+    a: Option<&serde_json::Value>,
+    b: Option<&serde_json::Value>,
+    mode: SortMode,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if mode == SortMode::Numeric => {
+            compare_numeric(&a.render(), &b.render())
+        }
+        (Some(serde_json::Value::Number(a)), Some(serde_json::Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        // numbers come before anything else, comparing them as text would not be a consistent order
+        (Some(serde_json::Value::Number(_)), Some(_)) => Ordering::Less,
+        (Some(_), Some(serde_json::Value::Number(_))) => Ordering::Greater,
+        (Some(a), Some(b)) if mode == SortMode::Natural => {
+            compare_natural(&a.render(), &b.render())
+        }
+        (Some(a), Some(b)) => a.render().cmp(&b.render()),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+pub(crate) fn sort_by_field<T>(items: &mut [(T, &serde_json::Value)], field: &str, mode: SortMode) {
+    items.sort_by(|(_, a), (_, b)| {
+        let (a, b) = (lookup_field(a, field), lookup_field(b, field));
+        let ordering = compare_fields(a, b, mode);
+
+        // items missing the field stay last in either direction
+        match mode {
+            SortMode::Descending if a.is_some() && b.is_some() => ordering.reverse(),
+            _ => ordering,
+        }
+    });
+}
+
+// interpret backslash escape sequences, keeping unknown ones as they are
+pub(crate) fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('0') => unescaped.push('\0'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+// join values within chunks of specific size, then join the chunks
+pub(crate) fn join_chunks(
+    output: &[String],
+    size: usize,
+    separator: &str,
+    chunk_separator: &str,
+) -> String {
+    output
+        .chunks(size)
+        .map(|chunk| chunk.join(separator))
+        .collect::<Vec<String>>()
+        .join(chunk_separator)
+}
+
+// keep as many leading values as fit in specific number of characters, followed by the overflow text
+pub(crate) fn join_within<F>(
+    output: &[String],
+    max_length: usize,
+    overflow: &str,
+    join: F,
+) -> String
+where
+    F: Fn(&[String]) -> String,
+{
+    let fits = |count: usize| {
+        let mut joined = join(&output[..count]);
+
+        if count < output.len() {
+            joined.push_str(&overflow.replace("{count}", &(output.len() - count).to_string()));
+        }
+
+        Some(joined).filter(|joined| joined.graphemes(true).count() <= max_length)
+    };
+
+    if let Some(joined) = fits(output.len()) {
+        return joined;
+    }
+
+    // the overflow text shrinks as fewer values are cut off, so the joined length may not grow with each value
+    if overflow.contains("{count}") {
+        return (0..output.len())
+            .rev()
+            .find_map(fits)
+            .unwrap_or_else(|| overflow.replace("{count}", &output.len().to_string()));
+    }
+
+    // the joined length grows with each value, search for the largest number of values that fit
+    let (mut low, mut high) = (0, output.len());
+    while low + 1 < high {
+        let mid = (low + high) / 2;
+        if fits(mid).is_some() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    fits(low).unwrap_or_else(|| overflow.replace("{count}", &output.len().to_string()))
+}
+
+// quote fields containing the delimiter, quotation marks or line breaks, doubling the quotation marks
+pub(crate) fn csv_field(field: &str, delimiter: &str) -> String {
+    if (!delimiter.is_empty() && field.contains(delimiter)) || field.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// leave JSON numbers as they are, quote anything else as ANSI string literal, doubling the quotation marks
+pub(crate) fn sql_literal(value: &str, literal: bool) -> String {
+    if literal && value.parse::<serde_json::Number>().is_ok() {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+// join path segments, collapsing repeated separators between and within them
+pub(crate) fn join_path(output: &[String], separator: &str) -> String {
+    let joined = output.join(separator);
+
+    if separator.is_empty() {
+        return joined;
+    }
+
+    let doubled = separator.repeat(2);
+    let mut path = joined;
+    while path.contains(&doubled) {
+        path = path.replace(&doubled, separator);
+    }
+
+    path
+}
+
+pub(crate) fn join_list(tag: &str, output: &[String]) -> String {
+    let mut list = format!("<{}>", tag);
+
+    for item in output {
+        list.push_str("<li>");
+        list.push_str(item);
+        list.push_str("</li>");
+    }
+
+    list.push_str(&format!("</{}>", tag));
+    list
+}
+
+pub(crate) fn join_output(
+    output: &[String],
+    separator: &str,
+    last_separator: Option<&str>,
+) -> String {
+    match (last_separator, output.split_last()) {
+        (Some(last_separator), Some((last, rest))) if !rest.is_empty() => {
+            format!("{}{}{}", rest.join(separator), last_separator, last)
+        }
+        _ => output.join(separator),
+    }
+}
+
+// hash parameters from the template, if any, falling back to specific options
+#[derive(Clone, Copy)]
+pub(crate) struct Params<'a, 'rc> {
+    pub(crate) helper: Option<&'a Helper<'rc>>,
+    pub(crate) defaults: &'a ConcatOptions,
+}
+
+impl<'a> Params<'a, '_> {
+    // hash parameter from the template, falling back to the default
+    pub(crate) fn get(&self, name: &str) -> Option<&'a serde_json::Value> {
+        self.helper
+            .and_then(|h| h.hash_get(name))
+            .map(|param| param.value())
+            .or_else(|| self.defaults.get(name))
+    }
+
+    // hash parameter set in the template itself
+    pub(crate) fn is_set(&self, name: &str) -> bool {
+        self.helper.is_some_and(|h| h.hash_get(name).is_some())
+    }
+
+    // hash parameter which could be disabled in the template, anything but `false` and `null` enables it
+    pub(crate) fn get_enabled(&self, name: &str) -> Option<&'a serde_json::Value> {
+        self.get(name).filter(|value| {
+            !matches!(
+                value,
+                serde_json::Value::Null | serde_json::Value::Bool(false)
+            )
+        })
+    }
+
+    // the "mode" hash parameter combines independent modes, e.g. `mode="values ul"`,
+    // each one taken from the template, falling back to the default
+    pub(crate) fn mode<T>(&self, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+        let find = |value: &serde_json::Value| value.render().split_whitespace().find_map(&parse);
+
+        self.helper
+            .and_then(|h| h.hash_get("mode"))
+            .and_then(|param| find(param.value()))
+            .or_else(|| self.defaults.get("mode").and_then(find))
+    }
+
+    // boolean hash parameter
+    pub(crate) fn flag(&self, name: &str) -> bool {
+        self.get_enabled(name).is_some()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Concat helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsConcat;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("concat", Box::new(HandlebarsConcat));
+///
+/// assert_eq!(h.render_template(r#"{{concat item1 item2}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "Value 1,Value 2");
+/// assert_eq!(h.render_template(r#"{{concat this separator=", "}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "item1, item2");
+/// assert_eq!(h.render_template(r#"{{#concat this separator=", "}}{{this}}{{/concat}}"#, &json!({"item1": "Value 1", "item2": "Value 2"})).expect("Render error"), "Value 1, Value 2");
+/// assert_eq!(h.render_template(r#"{{#concat "Form" this separator="" render_all=true}}<{{#if tag}}{{tag}}{{else}}{{this}}{{/if}}/>{{/concat}}"#, &json!({"key0":{"tag":"Input"},"key1":{"tag":"Select"},"key2":{"tag":"Button"}})).expect("Render error"), "<Form/><Input/><Select/><Button/>");
+/// ```
+///
+/// # Configuration
+///
+/// Hash parameter defaults could be set at registration time, template hash parameters still take precedence.
+/// Carrying them, the helper is no longer `Copy`, use `.clone()` where it used to be copied.
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsConcat;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("concat", Box::new(HandlebarsConcat::new().with_separator(", ").with_distinct(true).with_quotes('"')));
+///
+/// assert_eq!(h.render_template(r#"{{concat "One" "Two" "One"}}"#, &json!({})).expect("Render error"), r#""One", "Two""#);
+/// assert_eq!(h.render_template(r#"{{concat "One" "Two" "One" separator="|" distinct=false quotes=false}}"#, &json!({})).expect("Render error"), "One|Two|One");
+/// ```
+///
+/// The same options are available outside of templates as [`ConcatOptions`], see [`concat_values`].
+///
+/// # Behavior
+///
+/// The helper is looking for multiple arguments of type string, array or object. Arguments are being added to an output buffer and returned altogether as string.
+///
+/// The helper has few parameters modifying the behavior slightly. For example `distinct=true` eliminates duplicate values from the output buffer, while `quotes=true` in combination with `single_quote=true` wraps the values in quotation marks.
+///
+/// ## String
+/// ~~String arguments are added directly to the output buffer.~~
+/// As of `0.1.3` strings could be handled in one of two ways:
+/// 1. By default strings are added to the output buffer without modification (other than the quotation mark modifiers).
+/// 2. If you add a block template and use the `render_all` parameter, strings will be passed as `{{this}}` to the block template.
+///
+/// The block template rendering is disabled by default for backward compatibility.
+///
+/// ## Array
+/// ~~Array arguments are iterated and added as individual strings to the output buffer.~~
+/// As of `0.1.3` arrays could be handled in one of two ways:
+/// 1. By default array values are added as individual strings to the output buffer without modification (other than the quotation mark modifiers).
+/// 2. If you add a block template and use the `render_all` parameter, array values are passed as `{{this}}` to the block template.
+///
+/// The block template rendering is disabled by default for backward compatibility.
+///
+/// ## Object
+/// Object arguments could be handled two different ways:
+/// 1. By default only the object keys are being used and the values are ignored.
+///    Use `mode="values"` to use the values instead, or `mode="entries"` for `key=value` pairs.
+/// 2. If you add a block template the helper will use it to render the object value and
+///    concatenate it as string to the output buffer.
+///
+/// ## No arguments
+/// Calling the helper without arguments uses the current context instead, the same way as `{{concat this}}`.
+///
+/// Array items and object values rendered through the block template have access to the `@index`, `@first` and `@last` local variables. Object values also have access to `@key`.
+/// Both have access to the raw values of the previous and next ones through `@prev` and `@next`, along with their fields as `@prev_` and `@next_` local variables, e.g. `{{@prev_name}}`, unset for the first and last one respectively.
+/// All values rendered through the block template have access to `@count`, the running count of them starting from 1, and `@total`, the number of values rendered through the block template overall.
+/// Hash parameters not recognized by the helper, as well as the ones prefixed with `ctx_`, are available to the block template as `@extra_` local variables,
+/// e.g. `{{#concat users class="red" separator="" render_all=true}}<span class="{{@extra_class}}">{{name}}</span>{{/concat}}`, and all together as `@extra` object, e.g. `{{lookup @extra "class"}}`.
+/// The prefix is stripped, so `ctx_separator` is available as `{{@extra_separator}}`.
+///
+/// When used as a subexpression, e.g. `{{#each (concat a b)}}`, the helper returns the output buffer as an array of strings instead of joining it.
+///
+/// If the output buffer ends up empty, the `{{else}}` block template is rendered instead, when present.
+///
+/// Object rendering results are subject to `distinct`, `quotes` and `single_quote` modifier parameters, just like strings and arrays.
+///
+/// # Hash parameters
+///
+/// Parameters of optional features fail to render when their feature is not enabled.
+///
+/// * strict: Fail with a render error when a param could not be resolved, instead of skipping it, or when an unknown hash parameter is used. Parameters prefixed with `ctx_` are always accepted
+/// * separator: Set specific string to join elements with. Default is ","
+/// * last_separator: Set specific string to join the last two elements with. Default is `separator`. Ignored when using `chunk`
+/// * leading_separator: Prepend `separator` before the first value as well, or `chunk_separator` when chunked, e.g. `WHERE 1=1{{concat conds leading_separator=true separator=" AND "}}`, unless there are no values. Counted within `max_length`, ignored with the `ul`, `ol` and `markdown` output modes
+/// * trailing_separator: Append `separator` after the last value as well, or `chunk_separator` when chunked, e.g. trailing commas or trailing newline, unless there are no values. Counted within `max_length`, ignored with the `ul`, `ol` and `markdown` output modes
+/// * unescape_separator: Interpret `\n`, `\r`, `\t`, `\0` and `\\` escape sequences in `separator`, `last_separator` and `chunk_separator` coming from the context or registration time defaults. String literals in the template already support them
+/// * chunk: Break the output into groups of specific number of values, joined with `separator` within each group
+/// * chunk_separator: Set specific string to join groups of values with when using `chunk`. Default is a new line
+/// * distinct: Eliminate duplicates upon adding to output buffer. Use `distinct="ci"` to compare values case-insensitively, `distinct="last"` to keep the last occurrence of duplicates in its place instead of the first one, or both, e.g. `distinct="last ci"`
+/// * count_duplicates: Collapse duplicates into their first occurrence and append the number of occurrences, e.g. `apple (x3),pear`. Implies `distinct` and honors its modes
+/// * frequency: Output each distinct value with the number of its occurrences, most frequent first, e.g. `error:12,warn:3`. Implies `distinct` and honors its modes
+/// * pair_separator: Set specific string to join a value and its number of occurrences with in `frequency` mode, or the items of a `window`. Default is ":"
+/// * trim: Trim leading and trailing whitespace of each value, including block template output, before eliminating duplicates
+/// * trim_block: Trim leading and trailing whitespace of block template output only, skipping values with nothing but whitespace, e.g. from conditional block bodies, even with `keep_empty`
+/// * normalize: Compose each value, including block template output, into Unicode normalization form C, before eliminating duplicates, so composed and decomposed accents compare equal. Requires the `unicode-normalization` feature
+/// * case: Transform each value, including block template output, to `"lower"`, `"upper"` or `"capitalize"` case, before eliminating duplicates
+/// * slugify: Turn each value, including block template output, into URL-safe slug of lowercase letters and digits joined with dashes, before eliminating duplicates
+/// * keep_empty: Keep empty strings and block template output instead of skipping them. Array items and object keys are always kept, unless empty after `trim`
+/// * null: Set specific string to output in place of null values. By default null params and object values are skipped, while null array items render empty
+/// * bool_true: Set specific string to output in place of `true` values
+/// * bool_false: Set specific string to output in place of `false` values
+/// * precision: Format numbers with specific number of decimal places
+/// * thousands_separator: Group the integer part of numbers in thousands, joined with specific string
+/// * leading_zeros: Pad the integer part of numbers with zeros to specific number of digits
+/// * pointer: Descend into each param through a JSON pointer before processing it, e.g. `pointer="/results/items"`. Params the pointer does not resolve in are treated as `null`, or fail the rendering with `strict`
+/// * flatten: Expand nested arrays into individual values. Use `flatten=N` to expand specific number of levels only
+/// * window: Use overlapping windows of specific number of consecutive array items instead of individual ones, e.g. `window=2` for `a:b,b:c`. The block template receives each window as array `{{this}}` when used with `render_all`, otherwise its items are joined with `pair_separator`
+/// * recursive: Walk nested arrays and objects, collecting all scalar values instead of object keys. Ignored when rendering through the block template
+/// * pluck: Render a field of each array item and object value instead. Ignored when rendering through the block template
+/// * format: Render each value through a format string instead of a block template, e.g. `format="[{value}]"`. Placeholders are `{value}` for the value as it would be output otherwise, `{index}` for its position in its array or object, and `{key}` for its object key. Standalone values are at index 0 without a key. Ignored when rendering through the block template
+/// * map: Pipe each value through a `|` separated list of transforms, in order, before filtering and sorting it, e.g. `map="trim|lower|slug"`. Built-in transforms are `trim`, `lower`, `upper`, `capitalize`, `slug` and `normalize`, the latter requiring the `unicode-normalization` feature. Any other name calls the registered helper of that name with the value as its only param, e.g. `map="trim|my_helper"`, names being limited to letters, digits, `_` and `-`. Registered helpers take precedence over built-in transforms of the same name. Applies before `format`. Ignored when rendering through the block template
+/// * mode: Use object `"keys"` (default), `"values"` or `"entries"` when there is no block template. Use `"ul"` or `"ol"` to produce HTML list of the values instead of joining them with `separator`, HTML-escaping the values when there is no block template, `"markdown"` to produce Markdown list, `"csv"` to quote the values according to RFC 4180, failing to render along with `quotes`, `quote_char` or `chunk`, `"sql"` to produce ANSI SQL literals, e.g. `IN ({{concat ids mode="sql"}})`, leaving numbers bare and quoting strings, doubling single quotation marks. Backslashes are left as they are, so the literals are not safe on MySQL unless its `NO_BACKSLASH_ESCAPES` SQL mode is enabled, or `"path"` to join path segments with the platform path separator, unless `separator` is set, collapsing repeated separators. Combine both kinds, e.g. `mode="values ul"`
+/// * ordered: Number the items of `"markdown"` list
+/// * key_value_separator: Set specific string to join keys and values with in `"entries"` mode. Default is "="
+/// * where: Only use array items and object values with a field equal to specific value, e.g. `where="status=active"`
+/// * filter_key_prefix: Only use object entries with keys starting with specific string
+/// * filter_key_suffix: Only use object entries with keys ending with specific string
+/// * contains: Only use values containing specific string
+/// * starts_with: Only use values starting with specific string
+/// * ends_with: Only use values ending with specific string
+/// * gt, gte, lt, lte: Only use numeric values greater than, greater than or equal to, less than or less than or equal to specific number
+/// * match: Only use values matching specific regular expression. Requires the `regex` feature
+/// * exclude_match: Skip values matching specific regular expression. Requires the `regex` feature
+/// * filter_script: Only use values for which a Rhai script evaluates to `true`, exposing each one as `value`, e.g. `filter_script="value.len() > 3"`. Requires the `rhai` feature
+/// * sort_script: Sort values through a Rhai script comparing `a` and `b`, a negative result puts `a` first, e.g. `sort_script="a.len() - b.len()"`. Takes precedence over `sort`. Requires the `rhai` feature
+/// * parallel: Render the block template for each value on multiple threads, merging the results in order. Falls back to serial rendering when the template uses partials or decorators, or the context was changed. Requires the `rayon` feature, not available with `handlebars5`
+/// * distinct_by: Eliminate array items and object values sharing the same value of a field, regardless of how they render
+/// * quotes: Wrap each value in double quotation marks. Use `quotes="strings"` to leave numbers, booleans and `null` unquoted, as long as they still render as valid JSON, while strings are always quoted, e.g. `1, "two", "3"`, or `quotes="auto"` to quote only values containing the separator, whitespace or the quotation mark itself, e.g. `a,"b c",d`. Use `quotes="backtick"` or `quotes="bracket"` to quote SQL identifiers in `` `backticks` `` or `[brackets]` instead, doubling the closing delimiter inside of them regardless of `quote_char` and `quote_escape`
+/// * single_quote: Modifier of `quotes` to switch to single quotation mark instead
+/// * escape: Use `escape="html"` to HTML-escape each value, including block template output, before wrapping
+/// * quote_char: Wrap each value in specific string instead of quotation marks, implies `quotes`
+/// * quote_end: Close each value with specific string instead of `quote_char`, e.g. `quote_char="«" quote_end="»"`. Ignored without `quote_char`
+/// * quote_escape: Escape quotation marks inside of the values with `"backslash"` (default), `"double"` or `"none"`
+/// * json_encode: Serialize each value, including block template output, as JSON string literal with all quotation marks, backslashes and control characters escaped, for embedding in JSON or JavaScript. Takes precedence over `quotes` and `quote_char`
+/// * item_template: Render all values through specific inline template instead of the block template, e.g. `item_template="{{name}} <{{email}}>"`
+/// * partial: Render all values through specific registered partial or template instead of the block template, e.g. `partial="item_card"`. Takes precedence over `item_template`
+/// * render_all: Render all values using the block template, not just object values
+/// * sort: Sort values lexicographically before joining. Use `sort="desc"` for descending order, `sort="numeric"` to compare values as numbers or `sort="natural"` to compare runs of digits as numbers, e.g. `item2` before `item10`. Modes are case-insensitive and unknown ones fail to render
+/// * locale: Sort values lexicographically according to the collation rules of specific locale, e.g. `locale="de"`, ascending unless `sort` sets the direction. Requires the `icu` feature
+/// * sort_by: Sort array items and object values of all params together by a field of the source value instead, following the `sort` direction. Items missing the field come last in either direction. Numbers come before other values, unless `sort="numeric"` compares them all as numbers
+/// * sort_keys: Sort object entries alphabetically by key, before rendering, even if the `preserve_order` feature of `serde_json` is enabled. Values are still subject to `sort`
+/// * reverse: Reverse the order of values, after sorting
+/// * count: Output the number of values instead, after filtering, eliminating duplicates and applying `offset` and `limit`
+/// * default: Set specific string to output when there is nothing to concatenate. The `{{else}}` block takes precedence
+/// * max_length: Keep as many values as fit in specific number of characters of the joined output, counted as grapheme clusters, including the `overflow` text. Ignored with the `ul`, `ol` and `markdown` output modes
+/// * overflow: Set specific string to append when `max_length` cuts off values, with `{count}` replaced by the number of values cut off, e.g. `overflow=" +{count} more"`
+/// * offset: Skip specific number of leading values, after eliminating duplicates
+/// * limit: Keep at most specific number of values, after eliminating duplicates
+/// * item_max_length: Truncate each value, including block template output, to specific number of characters, counted as grapheme clusters so emoji and combining characters are kept whole, after eliminating duplicates
+/// * ellipsis: Set specific string to append to truncated values. Default is "…"
+/// * pad_start: Pad the start of each value to specific display width, e.g. `pad_start=3 pad_char="0"` for `007`. Wide CJK characters and emoji take up two columns, so fixed-width columns line up in plain text
+/// * pad_end: Pad the end of each value to specific display width
+/// * pad_char: Set specific character to pad values with. Default is space. Wide pad characters are completed with spaces where they don't fit
+/// * encode: Encode each value, including block template output, with `"base64"` or `"url"` percent-encoding of all but the unreserved characters, after truncating and padding, before escaping and wrapping, e.g. `{{#concat users encode="base64" render_all=true}}{{name}}:{{password}}{{/concat}}` for basic auth credentials
+/// * wrap_start: Prepend specific string to the joined output, only when there is something to concatenate
+/// * wrap_end: Append specific string to the joined output, only when there is something to concatenate
+/// * escape_output: Escape the joined output, or the `default`, through the registry escape function, which is HTML escaping by default
+/// * prefix: Prepend specific string to each value, outside of the quotation marks
+/// * suffix: Append specific string to each value, outside of the quotation marks
+/// * enumerate: Number each value, including block template output, starting from 1, e.g. `1. a, 2. b`. Numbers follow `offset` and `limit`, so they always start from 1
+/// * number_start: Set specific number to start `enumerate` from. Default is 1. Implies `enumerate`, as do all numbering options
+/// * number_pad: Pad numbers to specific width, arabic numbers with zeros, e.g. `01. a`, and the other styles with spaces
+/// * number_style: Number values with `"arabic"` numbers (default), `"alpha"` lowercase letters, e.g. `a. b. ... z. aa.`, or `"roman"` lowercase numerals, e.g. `i. ii. iii.`. Falls back to arabic numbers where the style has none, e.g. zero
+/// * number_separator: Set specific string between the number and the value. Default is ". ", e.g. `number_style="alpha" number_separator=") "` for `a) item`
+/// * indent: Indent every line of each value, including multi-line block template output, with specific number of spaces, e.g. `indent=2`, or specific string. Empty lines are left as they are. Applies last, after `prefix` and `enumerate`
+///
+/// # Example usage:
+///
+///
+/// Example with string literals:
+///
+/// ```handlebars
+/// {{concat "One" "Two" separator=", "}}
+/// ```
+///
+/// Result: `One, Two`
+///
+/// ---
+///
+/// ```handlebars
+/// {{concat "One" "Two" separator=", " quotes=true}}
+/// ```
+///
+/// Result: `"One", "Two"`
+///
+/// ---
+///
+/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"Three":3}`
+///
+/// ```handlebars
+/// {{concat s arr obj separator=", " distinct=true}}
+/// ```
+///
+/// Result: `One, Two, Three`
+///
+/// ---
+///
+/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}`:
+///
+/// ```handlebars
+/// {{#concat s arr obj separator=", " distinct=true}}{{label}}{{/concat}}
+/// ```
+///
+/// Result: `One, Two, Three, Four`
+///
+/// ---
+///
+/// Where `s` is `"One"`, `arr` is `["One", "Two"]` and `obj` is `{"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}`
+///
+/// ```handlebars
+/// {{#concat s arr obj separator=", " distinct=true render_all=true}}<{{#if label}}{{label}}{{else}}{{this}}{{/if}}/>{{/concat}}
+/// ```
+///
+/// Result: `<One/>, <Two/>, <Three/>, <Four/>`
+///
+/// ---
+///
+pub struct HandlebarsConcat {
+    defaults: ConcatOptions,
+}
+
+/// Concat helper without any registration time defaults, allows registration with `Box::new(HandlebarsConcat)`
+#[allow(non_upper_case_globals)]
+pub const HandlebarsConcat: HandlebarsConcat = HandlebarsConcat::new();
+
+impl HandlebarsConcat {
+    /// Create a concat helper without any registration time defaults
+    pub const fn new() -> Self {
+        HandlebarsConcat {
+            defaults: ConcatOptions::new(),
+        }
+    }
+
+    /// Set the default `separator`
+    pub fn with_separator(self, separator: impl Into<String>) -> Self {
+        self.with_default("separator", separator.into())
+    }
+
+    /// Enable or disable `distinct` by default
+    pub fn with_distinct(self, distinct: bool) -> Self {
+        self.with_default("distinct", distinct)
+    }
+
+    /// Wrap values in specific quotation mark by default, same as `quote_char`
+    pub fn with_quotes(self, quote: char) -> Self {
+        self.with_default("quote_char", quote.to_string())
+    }
+
+    /// Enable or disable `strict` mode by default
+    pub fn with_strict(self, strict: bool) -> Self {
+        self.with_default("strict", strict)
+    }
+
+    /// Enable or disable escaping of the output through the registry escape function by default
+    pub fn with_escape_output(self, escape_output: bool) -> Self {
+        self.with_default("escape_output", escape_output)
+    }
+
+    /// Set the default value of any hash parameter, e.g. `with_default("sort", "desc")`
+    pub fn with_default(mut self, name: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.defaults = self.defaults.with_param(name, value);
+        self
+    }
+
+    /// Use specific options as registration time defaults, replacing any previously set ones
+    pub fn with_options(mut self, options: ConcatOptions) -> Self {
+        self.defaults = options;
+        self
+    }
+
+    // hash parameters of a helper call, falling back to the registration time defaults
+    fn params<'a, 'rc>(&'a self, h: &'a Helper<'rc>) -> Params<'a, 'rc> {
+        Params {
+            helper: Some(h),
+            defaults: &self.defaults,
+        }
+    }
+
+    // resolve the templates and values of a helper call, then collect them through the shared core
+    fn collect<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<Vec<String>, RenderError> {
+        let params = self.params(h);
+
+        // fail on unknown hash parameters
+        if params.flag("strict") {
+            validate_hash(h)?;
+        }
+
+        // inline template compiled once per call, applied to all values unless there is a block template
+        let item_template = match params.get("item_template") {
+            Some(source) if !h.is_block() => {
+                let source = source.render();
+                Some(
+                    Template::compile(&source).map_err(|e| ConcatError::InvalidHashParam {
+                        name: "item_template",
+                        value: source.clone(),
+                        reason: e.to_string(),
+                    })?,
+                )
+            }
+            _ => None,
+        };
+
+        // registered partial or template applied to all values unless there is a block template, preferred over the item template
+        let partial = match params.get("partial") {
+            Some(name) if !h.is_block() => {
+                let name = name.render();
+                Some(
+                    get_partial(rc, r, &name).ok_or_else(|| ConcatError::InvalidHashParam {
+                        name: "partial",
+                        value: name.clone(),
+                        reason: "partial or template not found".to_string(),
+                    })?,
+                )
+            }
+            _ => None,
+        };
+
+        // partials copied out of the render context are rendered the same way as the item template
+        let (partial, item_template) = match partial {
+            Some(Cow::Borrowed(partial)) => (Some(partial), item_template),
+            Some(Cow::Owned(partial)) => (None, Some(partial)),
+            None => (None, item_template),
+        };
+
+        // operate on the current context when called without params, the same way as `{{concat this}}`
+        let implicit;
+        let values = if h.params().is_empty() {
+            implicit = [current_context(ctx, rc)];
+            &implicit[..]
+        } else {
+            h.params().as_slice()
+        };
+
+        let blocks = BlockRenderer {
+            helper: h,
+            template: h.template().or(partial),
+            item_template: item_template.as_ref(),
+            render_all: item_template.is_some() || partial.is_some(),
+            registry: r,
+            ctx,
+            rc,
+        };
+
+        collect_values(params, values, Some(blocks))
+    }
+}
+
+// everything needed to render values through a block, item or partial template, or through the `map` helper
+pub(crate) struct BlockRenderer<'a, 'reg, 'rc> {
+    pub(crate) helper: &'a Helper<'rc>,
+    pub(crate) template: Option<&'rc Template>,
+    pub(crate) item_template: Option<&'a Template>,
+    pub(crate) render_all: bool,
+    pub(crate) registry: &'reg Handlebars<'reg>,
+    pub(crate) ctx: &'rc Context,
+    pub(crate) rc: &'a mut RenderContext<'reg, 'rc>,
+}
+
+// collect, filter, sort and decorate all values, everything but the final join
+pub(crate) fn collect_values<'reg: 'rc, 'rc>(
+    params: Params<'_, 'rc>,
+    values: &[PathAndJson<'rc>],
+    mut blocks: Option<BlockRenderer<'_, 'reg, 'rc>>,
+) -> Result<Vec<String>, RenderError> {
+    for (name, enabled, reason) in FEATURE_PARAMS {
+        if let Some(value) = params.get_enabled(name).filter(|_| !enabled) {
+            return Err(ConcatError::InvalidHashParam {
+                name,
+                value: value.render(),
+                reason: reason.to_string(),
+            }
+            .into());
+        }
+    }
+
+    // CSV fields are quoted as needed and joined in a single row, leaving no room for these
+    if params.mode(OutputMode::parse) == Some(OutputMode::Csv) {
+        for name in ["chunk", "quote_char", "quotes"] {
+            if let Some(value) = params.get_enabled(name) {
+                return Err(ConcatError::InvalidHashParam {
+                    name,
+                    value: value.render(),
+                    reason: "not supported with `mode=\"csv\"`".to_string(),
+                }
+                .into());
+            }
+        }
+    }
+
+    // fail on params that could not be resolved instead of skipping them
+    let strict = params.flag("strict");
+
+    // filter output
+    let distinct = params
+        .get_enabled("distinct")
+        .map(|s| DistinctMode::parse(&s.render()));
+    let keep_last = params
+        .get_enabled("distinct")
+        .is_some_and(|s| DistinctMode::is_keep_last(&s.render()));
+
+    // collapse duplicates into a single value with the number of occurrences, implies "distinct"
+    let count_duplicates = params.flag("count_duplicates");
+
+    // output each distinct value with the number of occurrences, most frequent first, implies "distinct"
+    let pair_separator = params
+        .get("pair_separator")
+        .map(|s| s.render())
+        .unwrap_or_else(|| ":".to_string());
+    let frequency = params.flag("frequency").then(|| pair_separator.clone());
+
+    let distinct = match distinct {
+        None if count_duplicates || frequency.is_some() => Some(DistinctMode::CaseSensitive),
+        distinct => distinct,
+    };
+
+    // filter arrays and objects by a field of their values, before rendering
+    let distinct_by = params.get("distinct_by").map(|s| s.render());
+
+    // quotation marks set in the template take precedence over a registered custom quotation mark
+    let template_quotes =
+        !params.is_set("quote_char") && (params.is_set("quotes") || params.is_set("single_quote"));
+
+    // custom quotation mark, implies "quotes"
+    let quote_char = params
+        .get("quote_char")
+        .filter(|_| !template_quotes)
+        .map(|s| s.render());
+
+    // enable quotation marks wrapping
+    let quotes = params.flag("quotes") || quote_char.is_some();
+
+    // leave number and boolean values unquoted with `quotes="strings"`, or the ones that don't need quotes with `quotes="auto"`
+    let quote_mode = params
+        .get_enabled("quotes")
+        .map(|s| QuoteMode::parse(&s.render()))
+        .unwrap_or(QuoteMode::All);
+    let quote_separator = separator_param(params, "separator").unwrap_or_else(|| ",".to_string());
+
+    // as a modifier on top of "quotes", switches to single quotation
+    let single_quote = params.flag("single_quote");
+
+    let wrapper = if let Some(quote_char) = &quote_char {
+        quote_char.as_str()
+    } else if quotes {
+        if single_quote {
+            QUOTES_SINGLE
+        } else {
+            QUOTES_DOUBLE
+        }
+    } else {
+        ""
+    };
+
+    // closing quotation mark different from the custom opening one, e.g. guillemets
+    let quote_end = params
+        .get("quote_end")
+        .filter(|_| quote_char.is_some())
+        .map(|s| s.render());
+    let wrapper = (wrapper, quote_end.as_deref().unwrap_or(wrapper));
+
+    // serialize each value as JSON string literal, in place of the quotation marks
+    let json_encode = params.flag("json_encode");
+
+    // trim whitespace around each value, before eliminating duplicates
+    let trim = params.flag("trim");
+
+    // trim whitespace around block template output, skipping it when there is nothing else
+    let trim_block = params.flag("trim_block");
+
+    // compose each value into Unicode normalization form C, before eliminating duplicates
+    #[cfg(feature = "unicode-normalization")]
+    let normalize = params.flag("normalize");
+
+    // transform the case of each value, before eliminating duplicates
+    let case = params
+        .get("case")
+        .and_then(|s| CaseMode::parse(&s.render()));
+
+    // turn each value into URL-safe slug, before eliminating duplicates
+    let slugify = params.flag("slugify");
+
+    // escape each value before wrapping, block template output bypasses the registry escape function
+    let escape_html = params.get("escape").is_some_and(|s| s.render() == "html");
+
+    // how quotation marks inside of the values are escaped
+    let quote_escape = params
+        .get("quote_escape")
+        .map(|s| QuoteEscape::parse(&s.render()))
+        .unwrap_or(QuoteEscape::Backslash);
+
+    // truncate each value to specific number of characters, before escaping and wrapping
+    let item_max_length = params
+        .get("item_max_length")
+        .and_then(|s| s.render().parse::<usize>().ok());
+    let ellipsis = params
+        .get("ellipsis")
+        .map(|s| s.render())
+        .unwrap_or_else(|| "…".to_string());
+
+    // pad each value to specific number of characters, before escaping and wrapping
+    let pad_start = params
+        .get("pad_start")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .unwrap_or(0);
+    let pad_end = params
+        .get("pad_end")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .unwrap_or(0);
+    let pad_char = params
+        .get("pad_char")
+        .and_then(|s| s.render().chars().next())
+        .unwrap_or(' ');
+
+    // encode each value, after truncating and padding
+    let encoding = params
+        .get("encode")
+        .and_then(|s| Encoding::parse(&s.render()));
+
+    // arbitrary strings wrapped around each value, outside of the quotation marks
+    let prefix = params.get("prefix").map(|s| s.render()).unwrap_or_default();
+    let suffix = params.get("suffix").map(|s| s.render()).unwrap_or_default();
+
+    // number each value in the output, starting from 1, implied by any of the numbering options
+    let enumerate = params.flag("enumerate")
+        || [
+            "number_start",
+            "number_pad",
+            "number_style",
+            "number_separator",
+        ]
+        .iter()
+        .any(|name| params.get(name).is_some());
+    // indentation of every line of each value, either number of spaces or specific string
+    let indent = params
+        .get("indent")
+        .map(|s| {
+            let indent = s.render();
+            match indent.parse::<usize>() {
+                Ok(spaces) => " ".repeat(spaces),
+                Err(_) => indent,
+            }
+        })
+        .unwrap_or_default();
+
+    let numbering = enumerate.then(|| Numbering {
+        start: params
+            .get("number_start")
+            .and_then(|s| s.render().parse::<usize>().ok())
+            .unwrap_or(1),
+        pad: params
+            .get("number_pad")
+            .and_then(|s| s.render().parse::<usize>().ok())
+            .unwrap_or_default(),
+        style: params
+            .get("number_style")
+            .map(|s| NumberStyle::parse(&s.render()))
+            .unwrap_or(NumberStyle::Arabic),
+        separator: params
+            .get("number_separator")
+            .map(|s| s.render())
+            .unwrap_or_else(|| ". ".to_string()),
+    });
+
+    // sort the output buffer lexicographically before joining, ascending by default with a locale
+    let sort = match params.get("sort") {
+        Some(_) => params
+            .get_enabled("sort")
+            .map(|s| {
+                let value = s.render();
+                SortMode::parse(&value).ok_or(ConcatError::InvalidHashParam {
+                    name: "sort",
+                    value,
+                    reason: "expected `true`, `asc`, `desc`, `numeric` or `natural`".to_string(),
+                })
+            })
+            .transpose()?,
+        None => params.get_enabled("locale").map(|_| SortMode::Ascending),
+    };
+
+    // collate the output buffer according to specific locale
+    #[cfg(feature = "icu")]
+    let collator = match params.get("locale") {
+        Some(locale) => Some(create_collator(&locale.render())?),
+        None => None,
+    };
+
+    // filter and sort the output buffer through Rhai scripts
+    #[cfg(feature = "rhai")]
+    let filter_script = match params.get("filter_script") {
+        Some(source) => Some(Script::compile("filter_script", &source.render())?),
+        None => None,
+    };
+    #[cfg(feature = "rhai")]
+    let sort_script = match params.get("sort_script") {
+        Some(source) => Some(Script::compile("sort_script", &source.render())?),
+        None => None,
+    };
+
+    // descend into each param through a JSON pointer, before dispatching on its type
+    let pointer = params.get("pointer").map(|s| s.render());
+    if let Some(pointer) = &pointer {
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Err(ConcatError::InvalidHashParam {
+                name: "pointer",
+                value: pointer.clone(),
+                reason: "expected an empty string or a path starting with \"/\"".to_string(),
+            }
+            .into());
+        }
+    }
+    let segments = pointer.as_deref().map(pointer_segments).unwrap_or_default();
+
+    // sort object entries by key, regardless of the map ordering, e.g. insertion order with `serde_json/preserve_order`
+    let sort_keys = params.flag("sort_keys");
+
+    // sort arrays and objects by a field of their values, before rendering
+    let sort_by = params.get("sort_by").map(|s| s.render());
+
+    // reverse the output buffer, applied after sorting
+    let reverse = params.flag("reverse");
+
+    // slice the output buffer, applied after dedup
+    let offset = params
+        .get("offset")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .unwrap_or(0);
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .unwrap_or(usize::MAX);
+
+    // expand nested arrays into individual values, `flatten=true` expands all levels
+    let flatten = params
+        .get_enabled("flatten")
+        .map(|s| s.render().parse::<usize>().unwrap_or(usize::MAX))
+        .unwrap_or(0);
+
+    // overlapping windows of consecutive array items instead of individual ones
+    let window = params
+        .get("window")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .filter(|size| *size > 0);
+
+    // walk nested arrays and objects when there is no block template, collecting their scalar values
+    let recursive = params.flag("recursive");
+
+    // render a field of each array item or object value when there is no block template
+    let pluck = params.get("pluck").map(|s| s.render());
+
+    // render object keys, values or both when there is no block template,
+    // values are implied by "recursive" and "pluck"
+    let object_mode = params
+        .mode(ObjectMode::parse)
+        .unwrap_or(if recursive || pluck.is_some() {
+            ObjectMode::Values
+        } else {
+            ObjectMode::Keys
+        });
+
+    // only use array items and object values with a field equal to specific value, e.g. `where="status=active"`
+    let filter_where = params.get("where").and_then(|s| {
+        s.render()
+            .split_once('=')
+            .map(|(field, expected)| (field.trim().to_string(), expected.trim().to_string()))
+    });
+
+    // only use object entries with matching keys
+    let filter_key_prefix = params.get("filter_key_prefix").map(|s| s.render());
+    let filter_key_suffix = params.get("filter_key_suffix").map(|s| s.render());
+
+    // glue between keys and values in "entries" mode
+    let key_value_separator = params
+        .get("key_value_separator")
+        .map(|s| s.render())
+        .unwrap_or_else(|| KEY_VALUE_SEPARATOR.to_string());
+
+    // force all values through the block template
+    let render_all =
+        params.flag("render_all") || blocks.as_ref().is_some_and(|blocks| blocks.render_all);
+    let is_block = blocks
+        .as_ref()
+        .is_some_and(|blocks| blocks.template.is_some() || blocks.item_template.is_some());
+
+    // copy the adjacent values into each block only when the template refers to them
+    let adjacent = blocks.as_ref().is_some_and(|blocks| {
+        blocks
+            .template
+            .or(blocks.item_template)
+            .is_some_and(uses_adjacent_locals)
+    });
+
+    // values of HTML output modes are escaped, unless rendered through a template
+    let escape_html = escape_html
+        || (!is_block
+            && params
+                .mode(OutputMode::parse)
+                .is_some_and(OutputMode::is_html));
+
+    // SQL literals, quoting anything but numbers
+    let sql = params.mode(OutputMode::parse) == Some(OutputMode::Sql);
+
+    // lightweight template for values outside of the block template
+    let item_format = ItemFormat(params.get("format").map(|s| s.render()));
+
+    let format = ScalarFormat {
+        // placeholder for null values, skipped by default
+        null: params.get("null").map(|s| s.render()),
+        // custom rendering of booleans
+        bool_true: params.get("bool_true").map(|s| s.render()),
+        bool_false: params.get("bool_false").map(|s| s.render()),
+        // numeric formatting
+        precision: params
+            .get("precision")
+            .and_then(|s| s.render().parse::<usize>().ok()),
+        thousands_separator: params.get("thousands_separator").map(|s| s.render()),
+        leading_zeros: params
+            .get("leading_zeros")
+            .and_then(|s| s.render().parse::<usize>().ok()),
+    };
+
+    // decorated values are never empty, even if the raw value is
+    let decorated = quotes || !prefix.is_empty() || !suffix.is_empty();
+
+    // preserve empty values, e.g. for positional output
+    let keep_empty = decorated || params.flag("keep_empty");
+
+    let mut filters = Vec::new();
+
+    // filter values by substring
+    if let Some(needle) = params.get("contains") {
+        filters.push(ValueFilter::Contains(needle.render()));
+    }
+    if let Some(needle) = params.get("starts_with") {
+        filters.push(ValueFilter::StartsWith(needle.render()));
+    }
+    if let Some(needle) = params.get("ends_with") {
+        filters.push(ValueFilter::EndsWith(needle.render()));
+    }
+
+    // filter numeric values by range, anything that doesn't parse as a number is skipped
+    let range = [
+        ("gt", ValueFilter::GreaterThan as fn(f64) -> ValueFilter),
+        ("gte", ValueFilter::GreaterOrEqual),
+        ("lt", ValueFilter::LessThan),
+        ("lte", ValueFilter::LessOrEqual),
+    ];
+    for (name, filter) in range {
+        if let Some(bound) = params.get(name).and_then(|s| parse_number(&s.render())) {
+            filters.push(filter(bound));
+        }
+    }
+
+    // filter values by regular expression
+    #[cfg(feature = "regex")]
+    {
+        if let Some(pattern) = params.get("match") {
+            filters.push(ValueFilter::Match(compile_pattern(
+                "match",
+                &pattern.render(),
+            )?));
+        }
+        if let Some(pattern) = params.get("exclude_match") {
+            filters.push(ValueFilter::ExcludeMatch(compile_pattern(
+                "exclude_match",
+                &pattern.render(),
+            )?));
+        }
+    }
+
+    let mut output = OutputBuffer {
+        distinct,
+        keep_last,
+        count_duplicates,
+        frequency,
+        trim,
+        trim_block,
+        #[cfg(feature = "unicode-normalization")]
+        normalize,
+        case,
+        slugify,
+        keep_empty,
+        filters,
+        ..Default::default()
+    };
+
+    // pipeline of registered helpers and built-in transforms to pipe each value through, in order
+    let map = params
+        .get("map")
+        .map(|s| {
+            s.render()
+                .split('|')
+                .map(str::trim)
+                .filter(|step| !step.is_empty())
+                .map(MapStep::parse)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    // registered helpers are called from a template, through a single copy of the render context for all values
+    let mut map_render = match &blocks {
+        Some(blocks) if !map.is_empty() => Some((blocks.registry, blocks.ctx, blocks.rc.clone())),
+        _ => None,
+    };
+
+    let mut mapped = BlockOutput::default();
+    let mut map_value = |mut value: String, index: usize| -> Result<String, RenderError> {
+        for step in map.iter() {
+            if let (Some((registry, ctx, map_rc)), None | Some(true)) =
+                (&mut map_render, step.registered.get())
+            {
+                // keep the value around for the built-in transform until the helper is known to be registered
+                let subject = if step.registered.get().is_some() {
+                    std::mem::take(&mut value)
+                } else {
+                    value.clone()
+                };
+
+                let mut block = BlockContext::new();
+                block.set_base_value(serde_json::Value::String(subject));
+
+                map_rc.push_block(block);
+                let rendered = step.template.render(registry, ctx, map_rc, &mut mapped);
+                map_rc.pop_block();
+
+                match rendered {
+                    Ok(()) => {
+                        step.registered.set(Some(true));
+                        value = mapped.take();
+                        continue;
+                    }
+                    Err(source) if matches!(source.reason(), RenderErrorReason::HelperNotFound(missing) if *missing == step.name) =>
+                    {
+                        mapped.take();
+                        step.registered.set(Some(false));
+                    }
+                    Err(source) => return Err(render_failed(values, index, source)),
+                }
+            }
+
+            value = match step.transform {
+                Some(transform) => transform.apply(value),
+                None => {
+                    return Err(ConcatError::InvalidHashParam {
+                        name: "map",
+                        value: step.name.clone(),
+                        reason: if blocks.is_some() {
+                            "helper not found".to_string()
+                        } else {
+                            "registered helpers require a template to render through".to_string()
+                        },
+                    }
+                    .into())
+                }
+            };
+        }
+
+        Ok(value)
+    };
+
+    // values in order of appearance, rendering the block template only once all of them are known
+    let mut pending = Vec::new();
+    // source value of each pending value, for sorting them by field across params
+    let mut sources: Vec<&serde_json::Value> = Vec::new();
+
+    for (index, param) in values.iter().enumerate() {
+        if strict && param.is_value_missing() {
+            return Err(ConcatError::UnresolvedParam {
+                index,
+                path: param.relative_path().cloned(),
+            }
+            .into());
+        }
+
+        let value = match &pointer {
+            Some(pointer) => match param.value().pointer(pointer) {
+                Some(value) => value,
+                None if strict && !param.is_value_missing() => {
+                    return Err(ConcatError::UnresolvedPointer {
+                        index,
+                        path: param.relative_path().cloned(),
+                        pointer: pointer.clone(),
+                    }
+                    .into());
+                }
+                None => &serde_json::Value::Null,
+            },
+            None => param.value(),
+        };
+
+        match value {
+            serde_json::Value::Null => {
+                if let Some(value) = format.render(value) {
+                    pending.push(PendingValue::Value(
+                        item_format.apply(map_value(value, index)?, 0, None),
+                        true,
+                    ));
+                }
+            }
+            serde_json::Value::Bool(_)
+            | serde_json::Value::Number(_)
+            | serde_json::Value::String(_) => {
+                if is_block && render_all {
+                    // use block template to render strings
+                    pending.push(PendingValue::Block(
+                        Box::new(create_item_block(param, segments.clone(), value)),
+                        index,
+                    ));
+                } else {
+                    pending.push(PendingValue::Value(
+                        item_format.apply(
+                            map_value(format.render(value).unwrap_or_default(), index)?,
+                            0,
+                            None,
+                        ),
+                        !value.is_string(),
+                    ));
+                }
+            }
+            serde_json::Value::Array(ar) => {
+                let mut items = flatten_items(ar, flatten);
+                if let Some((field, expected)) = &filter_where {
+                    items.retain(|(_, item)| matches_field(item, field, expected));
+                }
+                if let Some(field) = &sort_by {
+                    sort_by_field(&mut items, field, sort.unwrap_or(SortMode::Ascending));
+                }
+                if let Some(field) = &distinct_by {
+                    items.retain(|(_, item)| output.is_distinct_field(item, field));
+                }
+
+                if let Some(size) = window {
+                    let len = items.len().saturating_sub(size - 1);
+
+                    for (position, window) in items.windows(size).enumerate() {
+                        if is_block && render_all {
+                            // use block template to render the window as array
+                            let mut block = BlockContext::new();
+                            block.set_base_value(serde_json::Value::Array(
+                                window.iter().map(|(_, item)| (*item).clone()).collect(),
+                            ));
+                            set_block_locals(&mut block, position, len);
+                            pending.push(PendingValue::Block(Box::new(block), index));
+                        } else {
+                            pending.push(PendingValue::Item(
+                                item_format.apply(
+                                    map_value(
+                                        window
+                                            .iter()
+                                            .filter_map(|(_, item)| format.render(item))
+                                            .collect::<Vec<String>>()
+                                            .join(&pair_separator),
+                                        index,
+                                    )?,
+                                    position,
+                                    None,
+                                ),
+                                false,
+                            ));
+                        }
+                    }
+                } else if is_block && render_all {
+                    // use block template to render array elements
+
+                    let len = items.len();
+
+                    for (position, (path, array_item)) in items.iter().enumerate() {
+                        let mut block =
+                            create_item_block(param, [&segments[..], path].concat(), array_item);
+                        set_block_locals(&mut block, position, len);
+                        if adjacent {
+                            set_adjacent_locals(
+                                &mut block,
+                                position.checked_sub(1).map(|prev| items[prev].1),
+                                items.get(position + 1).map(|(_, next)| *next),
+                            );
+                        }
+                        pending.push(PendingValue::Block(Box::new(block), index));
+                        sources.push(array_item);
+                    }
+                } else {
+                    for (position, (_, item)) in items.into_iter().enumerate() {
+                        let mut leaves = Vec::new();
+                        expand_item(item, pluck.as_deref(), recursive, &mut leaves);
+
+                        // null items render empty, unless there is a placeholder for them
+                        for item in leaves {
+                            let value = format.render(item).unwrap_or_default();
+                            pending.push(PendingValue::Item(
+                                item_format.apply(map_value(value, index)?, position, None),
+                                is_literal(item),
+                            ));
+                        }
+                        sources.resize(pending.len(), item);
+                    }
+                }
+            }
+            serde_json::Value::Object(o) => {
+                let mut entries = o
+                    .iter()
+                    .filter(|(key, _)| {
+                        filter_key_prefix
+                            .as_deref()
+                            .is_none_or(|prefix| key.starts_with(prefix))
+                            && filter_key_suffix
+                                .as_deref()
+                                .is_none_or(|suffix| key.ends_with(suffix))
+                    })
+                    .collect::<Vec<_>>();
+                if sort_keys {
+                    entries.sort_by_key(|(key, _)| *key);
+                }
+                if let Some((field, expected)) = &filter_where {
+                    entries.retain(|(_, item)| matches_field(item, field, expected));
+                }
+                if let Some(field) = &sort_by {
+                    sort_by_field(&mut entries, field, sort.unwrap_or(SortMode::Ascending));
+                }
+                if let Some(field) = &distinct_by {
+                    entries.retain(|(_, item)| output.is_distinct_field(item, field));
+                }
+
+                if is_block {
+                    // use block template to render objects
+
+                    let len = entries.len();
+
+                    for (position, (key, obj)) in entries.iter().enumerate() {
+                        let mut block = create_item_block(
+                            param,
+                            [&segments[..], std::slice::from_ref(*key)].concat(),
+                            obj,
+                        );
+                        set_block_locals(&mut block, position, len);
+                        if adjacent {
+                            set_adjacent_locals(
+                                &mut block,
+                                position.checked_sub(1).map(|prev| entries[prev].1),
+                                entries.get(position + 1).map(|(_, next)| *next),
+                            );
+                        }
+                        block.set_local_var("key", serde_json::Value::from(key.as_str()));
+                        pending.push(PendingValue::Block(Box::new(block), index));
+                        sources.push(obj);
+                    }
+                } else {
+                    match object_mode {
+                        ObjectMode::Keys => {
+                            // render keys only
+
+                            for (position, (key, item)) in entries.into_iter().enumerate() {
+                                pending.push(PendingValue::Item(
+                                    item_format.apply(
+                                        map_value(key.clone(), index)?,
+                                        position,
+                                        Some(key),
+                                    ),
+                                    false,
+                                ));
+                                sources.push(item);
+                            }
+                        }
+                        ObjectMode::Values => {
+                            for (position, (key, item)) in entries.into_iter().enumerate() {
+                                let mut leaves = Vec::new();
+                                expand_item(item, pluck.as_deref(), recursive, &mut leaves);
+
+                                for item in leaves {
+                                    if let Some(value) = format.render(item) {
+                                        pending.push(PendingValue::Item(
+                                            item_format.apply(
+                                                map_value(value, index)?,
+                                                position,
+                                                Some(key),
+                                            ),
+                                            is_literal(item),
+                                        ));
+                                    }
+                                }
+                                sources.resize(pending.len(), item);
+                            }
+                        }
+                        ObjectMode::Entries => {
+                            for (position, (key, item)) in entries.into_iter().enumerate() {
+                                let mut leaves = Vec::new();
+                                expand_item(item, pluck.as_deref(), false, &mut leaves);
+
+                                for item in leaves {
+                                    if let Some(value) = format.render(item) {
+                                        pending.push(PendingValue::Item(
+                                            item_format.apply(
+                                                map_value(
+                                                    format!(
+                                                        "{}{}{}",
+                                                        key, key_value_separator, value
+                                                    ),
+                                                    index,
+                                                )?,
+                                                position,
+                                                Some(key),
+                                            ),
+                                            false,
+                                        ));
+                                    }
+                                }
+                                sources.resize(pending.len(), item);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // scalar params and windows have no field to sort by
+        sources.resize(pending.len(), &serde_json::Value::Null);
+    }
+
+    // sort the items of all params together, the stable sort keeping ties in order of appearance
+    if let Some(field) = &sort_by {
+        let mut sorted = pending.into_iter().zip(sources).collect::<Vec<_>>();
+        sort_by_field(&mut sorted, field, sort.unwrap_or(SortMode::Ascending));
+        pending = sorted.into_iter().map(|(value, _)| value).collect();
+    }
+
+    let extra = blocks
+        .as_ref()
+        .map(|blocks| extra_hash(blocks.helper))
+        .unwrap_or_default();
+
+    let total = pending
+        .iter()
+        .filter(|value| matches!(value, PendingValue::Block(..)))
+        .count();
+
+    let mut count = 0;
+
+    for value in pending.iter_mut() {
+        if let PendingValue::Block(block, _) = value {
+            count += 1;
+            block.set_local_var("count", serde_json::Value::from(count));
+            block.set_local_var("total", serde_json::Value::from(total));
+            if !extra.is_empty() {
+                set_object_locals(block, "extra", serde_json::Value::from(extra.clone()));
+            }
+        }
+    }
+
+    // render the block template on multiple threads, unless it depends on render state that can't be shared
+    #[cfg(all(feature = "rayon", feature = "handlebars6"))]
+    let pending = match &mut blocks {
+        Some(blocks)
+            if params.flag("parallel")
+                && total > 1
+                && blocks.rc.context().is_none()
+                && !blocks.registry.dev_mode()
+                && blocks
+                    .template
+                    .or(blocks.item_template)
+                    .is_some_and(is_isolated_template) =>
+        {
+            render_parallel(
+                pending,
+                values,
+                blocks.template,
+                blocks.item_template,
+                blocks.registry,
+                blocks.ctx,
+                blocks.rc,
+            )?
+        }
+        _ => pending,
+    };
+
+    let mut content = BlockOutput::default();
+
+    for value in pending {
+        match (value, &mut blocks) {
+            (PendingValue::Value(value, literal), _) => output.push(value, literal),
+            (PendingValue::Item(value, literal), _) => output.push_item(value, literal),
+            #[cfg(all(feature = "rayon", feature = "handlebars6"))]
+            (PendingValue::Rendered(value), _) => output.push_block(value),
+            (PendingValue::Block(block, index), Some(blocks)) => {
+                render_block(
+                    blocks.template,
+                    blocks.item_template,
+                    *block,
+                    blocks.registry,
+                    blocks.ctx,
+                    blocks.rc,
+                    &mut content,
+                )
+                .map_err(|source| render_failed(values, index, source))?;
+
+                output.push_block(content.take());
+            }
+            (PendingValue::Block(..), None) => {}
+        }
+    }
+
+    let mut output = output.into_values();
+
+    #[cfg(feature = "rhai")]
+    if let Some(script) = &filter_script {
+        output = script.filter(output)?;
+    }
+
+    #[cfg(feature = "rhai")]
+    if let Some(script) = &sort_script {
+        script.sort(&mut output)?;
+    }
+
+    #[cfg(feature = "rhai")]
+    let sort = sort.filter(|_| sort_script.is_none());
+
+    if let (Some(mode), None) = (sort, &sort_by) {
+        #[cfg(feature = "icu")]
+        match &collator {
+            Some(collator) => sort_collated(&mut output, mode, collator),
+            None => sort_output(&mut output, mode),
+        }
+
+        #[cfg(not(feature = "icu"))]
+        sort_output(&mut output, mode);
+    }
+
+    if reverse {
+        output.reverse();
+    }
+
+    Ok(output
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|OutputValue { value, literal }| {
+            let item = match item_max_length {
+                Some(max_length) => truncate(value, max_length, &ellipsis),
+                None => value,
+            };
+            let item = apply_padding(item, pad_start, pad_end, pad_char);
+            let item = match encoding {
+                Some(encoding) => encoding.apply(item),
+                None => item,
+            };
+            let item = if escape_html {
+                html_escape(&item)
+            } else {
+                item
+            };
+
+            let item = if sql {
+                sql_literal(&item, literal)
+            } else if json_encode {
+                json_string(&item)
+            } else if let Some((open, close)) =
+                quote_mode.identifier_delimiters().filter(|_| quotes)
+            {
+                quote_identifier(&item, open, close)
+            } else {
+                let quoted =
+                    quotes && quote_mode.applies(&item, literal, &quote_separator, wrapper);
+                apply_wrapper(item, wrapper, quoted, quote_escape)
+            };
+
+            apply_affixes(item, &prefix, &suffix)
+        })
+        .enumerate()
+        .map(|(index, item)| match &numbering {
+            Some(numbering) => numbering.apply(index, item),
+            None => item,
+        })
+        .map(|item| indent_lines(item, &indent))
+        .collect::<Vec<String>>())
+}
+
+// separator hash parameter, interpreting escape sequences if asked to, hash strings could not contain line breaks otherwise
+pub(crate) fn separator_param(params: Params<'_, '_>, name: &str) -> Option<String> {
+    params.get(name).map(|s| {
+        if params.flag("unescape_separator") {
+            unescape(&s.render())
+        } else {
+            s.render()
+        }
+    })
+}
+
+// join the collected values according to the separators, output mode and wrapper parameters
+pub(crate) fn join_values(params: Params<'_, '_>, output: &[String]) -> String {
+    let separator_param = |name: &str| separator_param(params, name);
+
+    let separator = separator_param("separator").unwrap_or_else(|| ",".to_string());
+
+    // separator used between the last two elements only
+    let last_separator = separator_param("last_separator");
+
+    // break the output into groups of specific size, joined with the chunk separator
+    let chunk = params
+        .get("chunk")
+        .and_then(|s| s.render().parse::<usize>().ok())
+        .filter(|size| *size > 0);
+
+    let chunk_separator = separator_param("chunk_separator").unwrap_or_else(|| "\n".to_string());
+
+    // cap the joined output at specific number of characters, cutting at value boundaries
+    let max_length = params
+        .get("max_length")
+        .and_then(|s| s.render().parse::<usize>().ok());
+    let overflow = params
+        .get("overflow")
+        .map(|s| s.render())
+        .unwrap_or_default();
+
+    // markup to produce instead of joining with the separator
+    let output_mode = params.mode(OutputMode::parse).map(|mode| {
+        if mode == OutputMode::Markdown && params.flag("ordered") {
+            OutputMode::MarkdownOrdered
+        } else {
+            mode
+        }
+    });
+
+    // arbitrary strings wrapped around the joined output
+    let wrap_start = params
+        .get("wrap_start")
+        .map(|s| s.render())
+        .unwrap_or_default();
+    let wrap_end = params
+        .get("wrap_end")
+        .map(|s| s.render())
+        .unwrap_or_default();
+
+    // path segments are joined with the platform path separator, unless specified otherwise
+    let separator = if output_mode == Some(OutputMode::Path) && params.get("separator").is_none() {
+        std::path::MAIN_SEPARATOR.to_string()
+    } else {
+        separator
+    };
+
+    // separator of the outermost join, the chunk separator when chunked
+    let outer_separator = if chunk.is_some() && output_mode.is_none() {
+        chunk_separator.as_str()
+    } else {
+        separator.as_str()
+    };
+
+    // separator before the first value as well, e.g. when appending to a literal in the template
+    let leading = if params.flag("leading_separator") {
+        outer_separator
+    } else {
+        ""
+    };
+
+    // separator after the last value as well, e.g. trailing commas or newline
+    let trailing = if params.flag("trailing_separator") {
+        outer_separator
+    } else {
+        ""
+    };
+
+    // markup has no place for separators, nor for cutting it off
+    let markup = output_mode.filter(|mode| mode.is_markup());
+
+    let join = |values: &[String]| {
+        let joined = match (output_mode, chunk) {
+            (Some(mode), _) => mode.join(values, &separator),
+            (None, Some(size)) => join_chunks(values, size, &separator, &chunk_separator),
+            (None, None) => join_output(
+                values,
+                &separator,
+                // cut off output does not end with its last value
+                last_separator
+                    .as_deref()
+                    .filter(|_| values.len() == output.len()),
+            ),
+        };
+
+        // counted within the maximum length
+        if values.is_empty() || markup.is_some() {
+            joined
+        } else {
+            apply_affixes(joined, leading, trailing)
+        }
+    };
+
+    let joined = match (markup, max_length) {
+        (Some(_), _) if output.is_empty() => String::new(),
+        (Some(mode), _) => mode.join(output, &separator),
+        (None, Some(max_length)) => join_within(output, max_length, &overflow, join),
+        (None, None) => join(output),
+    };
+
+    // wrap the whole output, unless there is nothing to wrap
+    if output.is_empty() {
+        joined
+    } else {
+        apply_affixes(joined, &wrap_start, &wrap_end)
+    }
+}
+
+impl HelperDef for HandlebarsConcat {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let output = self.collect(h, r, ctx, rc)?;
+
+        if self.params(h).flag("count") {
+            return Ok(ScopedJson::Derived(serde_json::Value::from(output.len())));
+        }
+
+        Ok(ScopedJson::Derived(serde_json::Value::from(output)))
+    }
+
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let output = self.collect(h, r, ctx, rc)?;
+
+        write_values(self.params(h), &output, h, r, ctx, rc, out)
+    }
+}
+
+// write the joined values, their number, or the else block or default when there are none
+pub(crate) fn write_values<'reg: 'rc, 'rc>(
+    params: Params<'_, 'rc>,
+    output: &[String],
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    // escape whatever is written through the registry escape function, just like `{{expression}}`
+    let escape_output = |subject: String| {
+        if params.flag("escape_output") {
+            r.get_escape_fn()(&subject)
+        } else {
+            subject
+        }
+    };
+
+    // number of values instead of the values themselves
+    if params.flag("count") {
+        out.write(&output.len().to_string())?;
+        return Ok(());
+    }
+
+    if output.is_empty() {
+        if let Some(inverse) = h.inverse() {
+            // nothing to concatenate, use the else block instead
+            return inverse.render(r, ctx, rc, out);
+        }
+
+        if let Some(default) = params.get("default") {
+            out.write(&escape_output(default.render()))?;
+            return Ok(());
+        }
+    }
+
+    out.write(&escape_output(join_values(params, output)))?;
+
+    Ok(())
+}
+
+/// Register all concat helpers under their canonical names
+///
+/// * `concat`: [`HandlebarsConcat`](struct@HandlebarsConcat)
+/// * `concat_keys`: [`HandlebarsConcat`](struct@HandlebarsConcat) using object keys, same as `mode="keys"`
+/// * `concat_values`: [`HandlebarsConcat`](struct@HandlebarsConcat) using object values, same as `mode="values"`
+/// * `concat_entries`: [`HandlebarsConcat`](struct@HandlebarsConcat) using object entries, same as `mode="entries"`
+/// * `join`: [`HandlebarsJoin`]
+/// * `split`: [`HandlebarsSplit`]
+/// * `group_by`: [`HandlebarsGroupBy`]
+/// * `unique`: [`HandlebarsUnique`]
+/// * `zip`: [`HandlebarsZip`]
+/// * `concat_product`: [`HandlebarsProduct`]
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::register_concat_helpers;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// register_concat_helpers(&mut h);
+///
+/// assert_eq!(h.render_template(r#"{{concat "One" "Two"}}"#, &json!({})).expect("Render error"), "One,Two");
+/// ```
+pub fn register_concat_helpers(h: &mut Handlebars<'_>) {
+    h.register_helper("concat", Box::new(HandlebarsConcat));
+    h.register_helper(
+        "concat_keys",
+        Box::new(HandlebarsConcat::new().with_default("mode", "keys")),
+    );
+    h.register_helper(
+        "concat_values",
+        Box::new(HandlebarsConcat::new().with_default("mode", "values")),
+    );
+    h.register_helper(
+        "concat_entries",
+        Box::new(HandlebarsConcat::new().with_default("mode", "entries")),
+    );
+    h.register_helper("join", Box::new(HandlebarsJoin));
+    h.register_helper("split", Box::new(HandlebarsSplit));
+    h.register_helper("group_by", Box::new(HandlebarsGroupBy));
+    h.register_helper("unique", Box::new(HandlebarsUnique));
+    h.register_helper("zip", Box::new(HandlebarsZip));
+    h.register_helper("concat_product", Box::new(HandlebarsProduct));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(r#"{{concat 1 2}}"#, &String::new())
+                .expect("Render error"),
+            "1,2",
+            "Failed to concat numeric literals"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat "One" "Two"}}"#, &String::new())
+                .expect("Render error"),
+            "One,Two",
+            "Failed to concat literals"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat "One" "Two" separator=", "}}"#, &String::new())
+                .expect("Render error"),
+            "One, Two",
+            "Failed to concat literals with separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat "One" "Two" separator=", " quotes=true}}"#,
+                &String::new()
+            )
+            .expect("Render error"),
+            r#""One", "Two""#,
+            "Failed to concat literals with separator and quotes"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat "One" "Two" separator=", " quotes=true single_quote=true}}"#,
+                &String::new()
+            )
+            .expect("Render error"),
+            r#"'One', 'Two'"#,
+            "Failed to concat literals with separator and single quotation marks"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat s arr obj separator=", " quotes=true}}"#,
+                &json!({"arr": ["One", "Two", "Three"]})
+            )
+            .expect("Render error"),
+            r#""One", "Two", "Three""#,
+            "Failed to concat array with quotes"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat s arr obj separator=", " distinct=true}}"#,
+                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"Three":3}})
+            )
+            .expect("Render error"),
+            "One, Two, Three",
+            "Failed to concat literal, array and object"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat s arr obj separator=", " distinct=true}}{{label}}{{/concat}}"#,
+                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            "One, Two, Three, Four",
+            "Failed to concat literal, array and object using block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat s arr obj separator=", " distinct=true quotes=true}}{{label}}{{/concat}}"#,
+                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            r#""One", "Two", "Three", "Four""#,
+            "Failed to concat literal, array and object using block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat obj separator=", " quotes=true}}"#,
+                &json!({"obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            r#""key0", "key1", "key2""#,
+            "Failed to concat object keys with quotation marks and no distinction"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat s arr obj separator=", " distinct=true render_all=true}}<{{#if label}}{{label}}{{else}}{{this}}{{/if}}/>{{/concat}}"#,
+                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            r#"<One/>, <Two/>, <Three/>, <Four/>"#,
+            "Failed to concat literal, array and object using block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat s arr obj separator=", " distinct=true render_all=true quotes=true}}[{{#if label}}{{label}}{{else}}{{this}}{{/if}}]{{/concat}}"#,
+                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            r#""[One]", "[Two]", "[Three]", "[Four]""#,
+            "Failed to concat literal, array and object using block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat s arr obj separator=", " distinct=true render_all=true quotes=true}}[{{#if label}}{{label}}{{else}}{{@root/zero}}{{/if}}]{{/concat}}"#,
+                &json!({"zero":"Zero", "s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
+            ).expect("Render error"),
+            r#""[Zero]", "[Two]", "[Three]", "[Four]""#,
+            "Failed to concat literal, array and object using block template"
+        );
+    }
+
+    #[test]
+    fn last_separator() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator=", " last_separator=" and "}}"#,
+                &json!({"items": ["a", "b", "c"]})
+            )
+            .expect("Render error"),
+            "a, b and c",
+            "Failed to concat with last separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator=", " last_separator=" and "}}"#,
+                &json!({"items": ["a"]})
+            )
+            .expect("Render error"),
+            "a",
+            "Failed to concat single item with last separator"
+        );
+    }
+
+    #[test]
+    fn unescape_separator() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"items": ["a", "b", "c"], "sep": "\\n", "last": "\\t\\\\", "rows": "\\r\\n"});
+
+        assert_eq!(
+            h.render_template(r#"{{concat items separator="\n"}}"#, &data)
+                .expect("Render error"),
+            "a\nb\nc",
+            "Failed to concat with escape sequences of string literal"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat items separator=sep}}"#, &data)
+                .expect("Render error"),
+            "a\\nb\\nc",
+            "Failed to keep escape sequences of context values by default"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator=sep last_separator=last unescape_separator=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a\nb\t\\c",
+            "Failed to unescape separators"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items chunk=2 chunk_separator=rows unescape_separator=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,b\r\nc",
+            "Failed to unescape chunk separator"
+        );
+    }
+
+    #[test]
+    fn chunk() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"items": ["a", "b", "c", "d", "e"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat items separator=", " chunk=2}}"#, &data)
+                .expect("Render error"),
+            "a, b\nc, d\ne",
+            "Failed to concat in chunks"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items chunk=3 chunk_separator=" | " last_separator=" and "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,b,c | d,e",
+            "Failed to concat in chunks with chunk separator"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat items chunk=0}}"#, &data)
+                .expect("Render error"),
+            "a,b,c,d,e",
+            "Failed to ignore zero chunk size"
+        );
+    }
+
+    #[test]
+    fn prefix_and_suffix() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator="" prefix="<li>" suffix="</li>"}}"#,
+                &json!({"items": ["a", "b"]})
+            )
+            .expect("Render error"),
+            "<li>a</li><li>b</li>",
+            "Failed to concat with prefix and suffix"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat "a" obj prefix="[" suffix="]" quotes=true}}"#,
+                &json!({"obj": {"b": 1}})
+            )
+            .expect("Render error"),
+            r#"["a"],["b"]"#,
+            "Failed to concat with prefix, suffix and quotes"
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"items": ["a", "b"], "empty": []});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator=", " wrap_start="(" wrap_end=")"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "(a, b)",
+            "Failed to wrap output"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat empty wrap_start="(" wrap_end=")"}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to skip wrapping empty output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat empty wrap_start="(" wrap_end=")" default="-"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "-",
+            "Failed to skip wrapping default value"
+        );
+    }
+
+    #[test]
+    fn item_max_length() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"titles": ["Short", "Rather long title", "Überlänge"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat titles item_max_length=5}}"#, &data)
+                .expect("Render error"),
+            "Short,Rathe…,Überl…",
+            "Failed to truncate values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat titles item_max_length=6 ellipsis="..." quotes=true render_all=true}}{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#""Short","Rather...","Überlä...""#,
+            "Failed to truncate block template output with custom ellipsis"
+        );
+    }
+
+    #[test]
+    fn grapheme_truncation() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        // family emoji joined with zero width joiners, flag made of two regional indicators, decomposed accent
+        let data = json!({"words": [
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+            "\u{1f1e7}\u{1f1ec}\u{1f1e7}\u{1f1ec}",
+            "Cafe\u{301}s"
+        ]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat words item_max_length=1 ellipsis="~"}}"#, &data)
+                .expect("Render error"),
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}~,\u{1f1e7}\u{1f1ec}~,C~",
+            "Failed to truncate emoji sequences whole"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat words item_max_length=4 ellipsis=""}}"#, &data)
+                .expect("Render error"),
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467},\u{1f1e7}\u{1f1ec}\u{1f1e7}\u{1f1ec},Cafe\u{301}",
+            "Failed to keep combining characters with their base"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat words max_length=7 overflow="+{count}"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467},\u{1f1e7}\u{1f1ec}\u{1f1e7}\u{1f1ec}+1",
+            "Failed to measure joined output in grapheme clusters"
+        );
+    }
+
+    #[test]
+    fn padding() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"ids": [7, 42, 1234], "names": ["ab", "abcd"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat ids pad_start=3 pad_char="0"}}"#, &data)
+                .expect("Render error"),
+            "007,042,1234",
+            "Failed to pad values at the start"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat names pad_end=4 pad_char="." separator="|" quotes=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#""ab.."|"abcd""#,
+            "Failed to pad values at the end"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat names pad_start=3 pad_end=5}}"#, &data)
+                .expect("Render error"),
+            " ab  ,abcd ",
+            "Failed to pad values at both ends"
+        );
+
+        // report columns mixing latin, CJK, emoji and combining characters
+        let data = json!({"cells": ["abc", "漢字", "\u{1f600}", "e\u{301}", "ｘ"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat cells pad_end=6 separator="|"}}"#, &data)
+                .expect("Render error"),
+            "abc   |漢字  |\u{1f600}    |e\u{301}     |ｘ    ",
+            "Failed to pad values to display width"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat cells pad_start=5 pad_char="・"}}"#, &data)
+                .expect("Render error"),
+            "・abc, 漢字,・ \u{1f600},・・e\u{301},・ ｘ",
+            "Failed to pad values with wide character"
+        );
+    }
+
+    #[test]
+    fn encode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "values": ["", "f", "fo", "foo", "foob", "fooba", "foobar", "Über ✓"],
+            "users": [{"name": "admin", "password": "s3cr3t"}]
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat values encode="base64" keep_empty=true separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            " Zg== Zm8= Zm9v Zm9vYg== Zm9vYmE= Zm9vYmFy w5xiZXIg4pyT",
+            "Failed to encode values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users encode="base64" prefix="Basic " render_all=true}}{{name}}:{{password}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Basic YWRtaW46czNjcjN0",
+            "Failed to encode block template output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"https://example.com/{{concat parts separator="/" encode="url"}}?q={{concat query encode="url"}}"#,
+                &json!({"parts": ["docs", "a b/c", "Über?"], "query": "x&y=1#z~"})
+            )
+            .expect("Render error"),
+            "https://example.com/docs/a%20b%2Fc/%C3%9Cber%3F?q=x%26y%3D1%23z~",
+            "Failed to percent-encode values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat values encode="rot13" limit=2}}"#, &data)
+                .expect("Render error"),
+            ",f",
+            "Failed to ignore unknown encoding"
+        );
+    }
+
+    #[test]
+    fn enumerate() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"arr": ["a", "b", "c", "b"], "users": [{"name": "Ann"}, {"name": "Bob"}]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr enumerate=true separator=", "}}"#, &data)
+                .expect("Render error"),
+            "1. a, 2. b, 3. c, 4. b",
+            "Failed to enumerate values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr enumerate=true distinct=true offset=1 quotes=true separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#"1. "b" 2. "c""#,
+            "Failed to enumerate remaining values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users enumerate=true separator="\n" render_all=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "1. Ann\n2. Bob",
+            "Failed to enumerate block template output"
+        );
+    }
+
+    #[test]
+    fn format() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"s": "x", "arr": ["a", "{key}", "c"], "obj": {"k1": {"n": 1}, "k2": {"n": 2}}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat s arr format="[{value}]"}}"#, &data)
+                .expect("Render error"),
+            "[x],[a],[{key}],[c]",
+            "Failed to format values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr format="{index}:{value}{key}" separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0:a 1:{key} 2:c",
+            "Failed to format array values with their index"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat obj mode="values" pluck="n" format="{key}={value} {unknown}"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "k1=1 {unknown},k2=2 {unknown}",
+            "Failed to format object values with their keys"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj format="<{index}{value}>"}}"#, &data)
+                .expect("Render error"),
+            "<0k1>,<1k2>",
+            "Failed to format object keys"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr format="[{value}]" render_all=true}}{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,{key},c",
+            "Failed to ignore format with block template"
+        );
+    }
+
+    #[test]
+    fn map() {
+        use handlebars::{handlebars_helper, Handlebars};
+        use serde_json::json;
+
+        handlebars_helper!(upper: |s: str| s.to_uppercase());
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_helper("upper", Box::new(upper));
+
+        let data = json!({"arr": ["b", "a", "B"], "obj": {"k1": 1, "k2": 2}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr map="upper"}}"#, &data)
+                .expect("Render error"),
+            "B,A,B",
+            "Failed to map values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr map="upper" distinct=true sort=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "A,B",
+            "Failed to map values before filtering and sorting"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat obj map="upper" format="{key}={value}" separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "k1=K1 k2=K2",
+            "Failed to map values before formatting"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr map="upper" render_all=true}}<{{this}}>{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<b>,<a>,<B>",
+            "Failed to ignore map with block template"
+        );
+
+        let error = h
+            .render_template(r#"{{concat arr map="missing"}}"#, &data)
+            .expect_err("Failed to report missing helper");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::InvalidHashParam { name: "map", value, .. }) if value == "missing"
+            ),
+            "Failed to report missing helper: {:?}",
+            error
+        );
+
+        h.register_partial("secret", "s3cr3t")
+            .expect("Failed to register partial");
+        for step in ["x}}{{> secret", "lookup ../..", "../name", "x this}}{{this"] {
+            let error = h
+                .render_template(&format!(r#"{{{{concat arr map="{}"}}}}"#, step), &data)
+                .expect_err("Failed to reject map step");
+            assert!(
+                matches!(
+                    ConcatError::from_render_error(&error),
+                    Some(ConcatError::InvalidHashParam { name: "map", value, .. }) if value == step
+                ),
+                "Failed to reject map step `{}`: {:?}",
+                step,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn map_pipeline() {
+        use handlebars::{handlebars_helper, Handlebars};
+        use serde_json::json;
+
+        handlebars_helper!(reverse: |s: str| s.chars().rev().collect::<String>());
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_helper("reverse", Box::new(reverse));
+
+        // registered helpers take precedence over built-in transforms of the same name
+        handlebars_helper!(slug: |s: str| format!("slug({})", s));
+
+        let mut custom = h.clone();
+        custom.register_helper("slug", Box::new(slug));
+
+        assert_eq!(
+            custom
+                .render_template(
+                    r#"{{concat arr map="trim|slug|upper"}}"#,
+                    &json!({"arr": [" a b "]})
+                )
+                .expect("Render error"),
+            "SLUG(A B)",
+            "Failed to prefer registered helper over built-in transform"
+        );
+
+        let data = json!({"arr": [" Hello World ", "hello  world", "Other"]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr map="trim|lower|slug" distinct=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "hello-world,other",
+            "Failed to chain transforms before eliminating duplicates"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr map=" upper || reverse|trim " separator="/"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "DLROW OLLEH/DLROW  OLLEH/REHTO",
+            "Failed to chain transforms with registered helper"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr map="reverse|capitalize" separator="/"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            " dlroW olleH /Dlrow  olleh/RehtO",
+            "Failed to apply transforms in order"
+        );
+        assert_eq!(
+            concat_values(
+                &[json!(" A b ")],
+                &ConcatOptions::new().with_param("map", "trim|slug")
+            )
+            .expect("Concat error"),
+            "a-b",
+            "Failed to apply built-in transforms without template"
+        );
+        assert!(
+            matches!(
+                concat_values(
+                    &[json!(" A b ")],
+                    &ConcatOptions::new().with_param("map", "reverse|trim")
+                )
+                .as_ref()
+                .map_err(ConcatError::from_render_error),
+                Err(Some(ConcatError::InvalidHashParam { name: "map", value, .. })) if value == "reverse"
+            ),
+            "Failed to reject registered helpers without template"
+        );
+        assert!(
+            h.render_template(r#"{{concat arr map="trim|missing"}}"#, &data)
+                .is_err(),
+            "Failed to report missing helper in pipeline"
+        );
+    }
+
+    #[test]
+    fn indent() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b"], "obj": {"x": {"v": 1}, "y": {"v": 2}}});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr indent=2 prefix="- " unescape_separator=true separator="\n"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "  - a\n  - b",
+            "Failed to indent values with spaces"
+        );
+        assert_eq!(
+            h.render_template(
+                "{{#concat obj indent=\">>\" separator=\"\"}}\n{{@key}}:\n\n  v: {{v}}\n{{/concat}}",
+                &data
+            )
+            .expect("Render error"),
+            ">>x:\n\n>>  v: 1\n>>y:\n\n>>  v: 2\n",
+            "Failed to indent every line of block output"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr indent=0 enumerate=true}}"#, &data)
+                .expect("Render error"),
+            "1. a,2. b",
+            "Failed to skip empty indentation"
+        );
+    }
+
+    #[test]
+    fn leading_separator() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"conds": ["a = 1", "b = 2"], "empty": []});
+
+        assert_eq!(
+            h.render_template(
+                r#"WHERE 1=1{{concat conds leading_separator=true separator=" AND "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "WHERE 1=1 AND a = 1 AND b = 2",
+            "Failed to prepend leading separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"WHERE 1=1{{concat empty leading_separator=true separator=" AND "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "WHERE 1=1",
+            "Failed to skip leading separator without values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat conds leading_separator=true trailing_separator=true separator="|" wrap_start="[" wrap_end="]"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[|a = 1|b = 2|]",
+            "Failed to combine leading and trailing separators"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat conds leading_separator=true max_length=6}}"#,
+                &data
+            )
+            .expect("Render error"),
+            ",a = 1",
+            "Failed to count leading separator within maximum length"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat conds leading_separator=true chunk=1 chunk_separator="|"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "|a = 1|b = 2",
+            "Failed to prepend chunk separator to chunked output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat ids leading_separator=true mode="sql" separator=", "}}"#,
+                &json!({"ids": [1, "b"]})
+            )
+            .expect("Render error"),
+            ", 1, 'b'",
+            "Failed to prepend leading separator to SQL output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat parts leading_separator=true mode="path" separator="/"}}"#,
+                &json!({"parts": ["usr", "local/", "bin"]})
+            )
+            .expect("Render error"),
+            "/usr/local/bin",
+            "Failed to prepend leading separator to path output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat conds leading_separator=true mode="markdown"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "- a = 1\n- b = 2",
+            "Failed to ignore leading separator with markup"
+        );
+    }
+
+    #[test]
+    fn trailing_separator() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b"], "empty": []});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr trailing_separator=true separator=", " wrap_start="[" wrap_end="]"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[a, b, ]",
+            "Failed to append trailing separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr trailing_separator=true separator="\n" last_separator=" and "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a and b\n",
+            "Failed to append trailing separator after last separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat empty trailing_separator=true default="none"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to skip trailing separator without values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr trailing_separator=true mode="ul"}}"#, &data)
+                .expect("Render error"),
+            "<ul><li>a</li><li>b</li></ul>",
+            "Failed to ignore trailing separator with markup"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr trailing_separator=true max_length=3}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,",
+            "Failed to count trailing separator within maximum length"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr trailing_separator=true chunk=1 chunk_separator="|"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a|b|",
+            "Failed to append chunk separator to chunked output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr mode="csv" separator="\n" trailing_separator=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a\nb\n",
+            "Failed to append trailing separator to CSV output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr mode="sql" trailing_separator=true max_length=7}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "'a',",
+            "Failed to count trailing separator of SQL output within maximum length"
+        );
+    }
+
+    #[test]
+    fn numbering() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b", "c"], "many": (0..30).collect::<Vec<_>>()});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr number_style="alpha" number_separator=") " separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a) a b) b c) c",
+            "Failed to number values with letters"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr enumerate=true number_style="roman" number_start=8 number_pad=4 separator="|"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "viii. a|  ix. b|   x. c",
+            "Failed to number values with padded roman numerals"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr number_start=0 number_pad=3 number_separator=":" separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "000:a 001:b 002:c",
+            "Failed to number values with padded arabic numbers"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat many number_style="alpha" number_separator="" offset=24 limit=4 separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a24 b25 c26 d27",
+            "Failed to restart numbering after offset"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat many number_style="alpha" number_start=25 number_separator="" limit=4 separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "y0 z1 aa2 ab3",
+            "Failed to continue letters past z"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr number_style="roman" number_start=0 limit=2 separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0. a i. b",
+            "Failed to fall back to arabic numbers"
+        );
+    }
+
+    #[test]
+    fn limit_and_offset() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"s": "a", "arr": ["a", "b", "c", "d"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat s arr distinct=true limit=2}}"#, &data)
+                .expect("Render error"),
+            "a,b",
+            "Failed to concat with limit"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat s arr distinct=true offset=1}}"#, &data)
+                .expect("Render error"),
+            "b,c,d",
+            "Failed to concat with offset"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr offset=1 limit=2}}"#, &data)
+                .expect("Render error"),
+            "b,c",
+            "Failed to concat with offset and limit"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr offset=10}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to concat with offset past the end"
+        );
+    }
+
+    #[test]
+    fn max_length() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"items": ["alpha", "beta", "gamma", "delta"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat items separator=", " max_length=100}}"#, &data)
+                .expect("Render error"),
+            "alpha, beta, gamma, delta",
+            "Failed to keep output within max length"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat items separator=", " max_length=20}}"#, &data)
+                .expect("Render error"),
+            "alpha, beta, gamma",
+            "Failed to cut output at value boundary"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator=", " last_separator=" and " max_length=20 overflow=" +{count} more"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "alpha, beta +2 more",
+            "Failed to cut output with overflow text"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat items max_length=3 overflow="…"}}"#, &data)
+                .expect("Render error"),
+            "…",
+            "Failed to cut all values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat items separator="" max_length=6 overflow="{count}{count}"}}"#,
+                &json!({"items": ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m"]})
+            )
+            .expect("Render error"),
+            "abcd99",
+            "Failed to keep the most values when the overflow text shrinks"
+        );
+    }
+
+    #[test]
+    fn sort() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"s": "b", "arr": ["c", "a"], "obj": {"d": 1}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj sort=true}}"#, &data)
+                .expect("Render error"),
+            "a,b,c,d",
+            "Failed to concat with ascending sort"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj sort="desc"}}"#, &data)
+                .expect("Render error"),
+            "d,c,b,a",
+            "Failed to concat with descending sort"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj sort=true limit=2}}"#, &data)
+                .expect("Render error"),
+            "a,b",
+            "Failed to concat with sort and limit"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj sort="DESC"}}"#, &data)
+                .expect("Render error"),
+            "d,c,b,a",
+            "Failed to match sort mode case-insensitively"
+        );
+
+        for mode in ["dsc", "1", ""] {
+            let error = h
+                .render_template(&format!(r#"{{{{concat s arr sort="{}"}}}}"#, mode), &data)
+                .expect_err("Failed to report unknown sort mode");
+            assert!(
+                matches!(
+                    ConcatError::from_render_error(&error),
+                    Some(ConcatError::InvalidHashParam { name: "sort", value, .. }) if value == mode
+                ),
+                "Failed to report unknown sort mode: {:?}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn sort_keys() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"obj": {"zeta": {"n": 1}, "alpha": {"n": 2}, "mid": {"n": 3}}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat obj sort_keys=true}}"#, &data)
+                .expect("Render error"),
+            "alpha,mid,zeta",
+            "Failed to concat sorted object keys"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj sort_keys=true}}{{@key}}={{n}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "alpha=2,mid=3,zeta=1",
+            "Failed to render sorted object entries through block template"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj sort_keys=true reverse=true}}"#, &data)
+                .expect("Render error"),
+            "zeta,mid,alpha",
+            "Failed to reverse sorted object keys"
+        );
+    }
+
+    #[test]
+    fn sort_by() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "arr": [{"label": "Ten", "order": 10}, {"label": "Two", "order": 2}, {"label": "One", "order": 1}],
+            "obj": {"key0": {"label": "B", "order": 3}, "key1": {"label": "A", "order": 2}, "key2": {"label": "C"}}
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true sort_by="order"}}{{label}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "One,Two,Ten",
+            "Failed to concat array sorted by numeric field"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true sort_by="k" separator=" "}}{{k}}{{/concat}}"#,
+                &json!({"arr": [{"k": "1a"}, {"k": 10}, {"k": "3c"}, {"k": 9}, {"k": 30}, {"k": "2b"}, {"k": 3}]})
+            )
+            .expect("Render error"),
+            "3 9 10 30 1a 2b 3c",
+            "Failed to concat array sorted by field of mixed types"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj sort_by="order" sort="desc"}}{{label}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "B,A,C",
+            "Failed to concat object values sorted by field in descending order"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat a b render_all=true sort_by="n"}}{{n}}{{/concat}}"#,
+                &json!({"a": [{"n": 2}, {"n": 4}], "b": [{"n": 3}, {"n": 1}]})
+            )
+            .expect("Render error"),
+            "1,2,3,4",
+            "Failed to concat items of several params sorted by field"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat a b sort_by="n" sort="desc" pluck="v"}}"#,
+                &json!({"a": [{"v": "x"}, {"n": 1, "v": "y"}], "b": [{"n": 2, "v": "z"}]})
+            )
+            .expect("Render error"),
+            "z,y,x",
+            "Failed to keep items missing the sort field last in descending order"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj sort_by="order"}}"#, &data)
+                .expect("Render error"),
+            "key1,key0,key2",
+            "Failed to concat object keys sorted by field"
+        );
+    }
+
+    #[test]
+    fn sort_numeric() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr sort="numeric"}}"#,
+                &json!({"arr": [2, 10, 1]})
+            )
+            .expect("Render error"),
+            "1,2,10",
+            "Failed to concat with numeric sort"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr sort="numeric" quotes=true}}"#,
+                &json!({"arr": ["2", "x", "10", "1.5"]})
+            )
+            .expect("Render error"),
+            r#""1.5","2","10","x""#,
+            "Failed to concat with numeric sort, quotes and non-numeric values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr sort="numeric" limit=4}}"#,
+                &json!({"arr": [919, "nan", 3, "x", "NaN", 838, -1, "inf"]})
+            )
+            .expect("Render error"),
+            "-1,3,838,919",
+            "Failed to concat with numeric sort, skipping not a number values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true sort_by="n" sort="numeric"}}{{n}}{{/concat}}"#,
+                &json!({"arr": [{"n": "10"}, {"n": "9"}]})
+            )
+            .expect("Render error"),
+            "9,10",
+            "Failed to concat with numeric sort by field"
+        );
+    }
+
+    #[test]
+    fn sort_natural() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr sort="natural"}}"#,
+                &json!({"arr": ["item10", "item2", "item02", "item1b", "item", "item1a"]})
+            )
+            .expect("Render error"),
+            "item,item1a,item1b,item2,item02,item10",
+            "Failed to concat with natural sort"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat obj sort="natural"}}"#,
+                &json!({"obj": {"v10": 1, "v9": 2, "v1": 3}})
+            )
+            .expect("Render error"),
+            "v1,v9,v10",
+            "Failed to concat object keys with natural sort"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true sort_by="n" sort="natural"}}{{n}}{{/concat}}"#,
+                &json!({"arr": [{"n": "a10"}, {"n": "a9"}]})
+            )
+            .expect("Render error"),
+            "a9,a10",
+            "Failed to concat with natural sort by field"
+        );
+    }
+
+    #[cfg(feature = "icu")]
+    #[test]
+    fn sort_locale() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["Zebra", "Äpfel", "apfel", "Birne"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr sort=true locale="de"}}"#, &data)
+                .expect("Render error"),
+            "apfel,Äpfel,Birne,Zebra",
+            "Failed to concat with locale collation"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr sort="desc" locale="de"}}"#, &data)
+                .expect("Render error"),
+            "Zebra,Birne,Äpfel,apfel",
+            "Failed to concat with descending locale collation"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr locale="de"}}"#, &data)
+                .expect("Render error"),
+            "apfel,Äpfel,Birne,Zebra",
+            "Failed to imply ascending sort with locale"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr sort=false locale="de"}}"#, &data)
+                .expect("Render error"),
+            "Zebra,Äpfel,apfel,Birne",
+            "Failed to disable sort with locale"
+        );
+        assert!(
+            h.render_template(r#"{{concat arr sort=true locale="!!"}}"#, &data)
+                .is_err(),
+            "Failed to report invalid locale"
+        );
+    }
+
+    #[test]
+    fn reverse() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["b", "c", "a"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr reverse=true}}"#, &data)
+                .expect("Render error"),
+            "a,c,b",
+            "Failed to concat in reverse"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr sort=true reverse=true limit=2}}"#, &data)
+                .expect("Render error"),
+            "c,b",
+            "Failed to concat in reverse with sort and limit"
+        );
+    }
+
+    #[test]
+    fn distinct_case_insensitive() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"s": "Rust", "arr": ["rust", "Go"], "obj": {"RUST": 1, "go": 2}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj distinct="ci"}}"#, &data)
+                .expect("Render error"),
+            "Rust,Go",
+            "Failed to concat with case-insensitive distinct"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat s arr obj distinct=true}}"#, &data)
+                .expect("Render error"),
+            "Rust,rust,Go,RUST,go",
+            "Failed to concat with case-sensitive distinct"
+        );
+    }
+
+    #[test]
+    fn distinct_last() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"defaults": ["a", "b", "c"], "overrides": ["A", "b"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat defaults overrides distinct="last"}}"#, &data)
+                .expect("Render error"),
+            "a,c,A,b",
+            "Failed to keep last occurrence of duplicates"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat defaults overrides distinct="last ci"}}"#, &data)
+                .expect("Render error"),
+            "c,A,b",
+            "Failed to keep last occurrence of case-insensitive duplicates"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat defaults overrides distinct=true}}"#, &data)
+                .expect("Render error"),
+            "a,b,c,A",
+            "Failed to keep first occurrence of duplicates"
+        );
+    }
+
+    #[test]
+    fn count_duplicates() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"fruits": ["apple", "pear", "Apple", "apple", "plum", "pear"]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat fruits count_duplicates=true separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "apple (x2), pear (x2), Apple, plum",
+            "Failed to count duplicates"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat fruits count_duplicates=true distinct="ci" separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "apple (x3), pear (x2), plum",
+            "Failed to count case-insensitive duplicates"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat fruits count_duplicates=true distinct="last" separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Apple, apple (x2), plum, pear (x2)",
+            "Failed to count duplicates kept in place of the last occurrence"
+        );
+    }
+
+    #[test]
+    fn frequency() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"levels": ["warn", "error", "info", "error", "Warn", "error", "warn"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat levels frequency=true separator=", "}}"#, &data)
+                .expect("Render error"),
+            "error:3, warn:2, info:1, Warn:1",
+            "Failed to output value frequencies"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat levels frequency=true distinct="ci" pair_separator="=" limit=2}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "warn=3,error=3",
+            "Failed to output case-insensitive value frequencies"
+        );
+    }
+
+    #[test]
+    fn distinct_by() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "arr": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Alice"}, {"id": 1, "name": "Alice"}],
+            "obj": {"key0": {"id": 2, "name": "Bob"}, "key1": {"id": 3, "name": "Carol"}}
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr obj render_all=true distinct_by="id"}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Alice,Alice,Carol",
+            "Failed to concat with distinct by field"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr obj render_all=true distinct_by="id" distinct=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Alice,Carol",
+            "Failed to concat with distinct by field and distinct output"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj distinct_by="name"}}"#, &data)
+                .expect("Render error"),
+            "key0,key1",
+            "Failed to concat object keys with distinct by field"
+        );
+    }
+
+    #[test]
+    fn distinct_within_param() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr distinct=true}}"#,
+                &json!({"arr": ["a", "b", "a", "c", "b"]})
+            )
+            .expect("Render error"),
+            "a,b,c",
+            "Failed to eliminate duplicates within a single array"
+        );
+    }
+
+    #[test]
+    fn block_context_paths() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "title": "T",
+            "data": {"items": [{"name": "a"}, {"name": "b"}], "map": {"k.0": {"name": "c"}, "k/1": {"name": "d"}}}
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat data.items data.map render_all=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,b,c,d",
+            "Failed to concat nested paths using block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat data.items render_all=true}}{{../title}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Ta,Tb",
+            "Failed to resolve relative paths from block template"
+        );
+    }
+
+    #[test]
+    fn block_locals() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b", "c"], "obj": {"key0": "x", "key1": "y"}});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true}}{{@index}}:{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0:a,1:b,2:c",
+            "Failed to expose @index to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true separator=""}}{{#if @first}}[{{/if}}{{this}}{{#unless @last}}|{{else}}]{{/unless}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[a|b|c]",
+            "Failed to expose @first and @last to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj}}{{@index}}{{this}}{{#if @last}}!{{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0x,1y!",
+            "Failed to expose block locals when rendering object values"
+        );
+    }
+
+    #[test]
+    fn block_key() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj separator="&"}}{{@key}}={{this}}{{/concat}}"#,
+                &json!({"obj": {"a": 1, "b": 2}})
+            )
+            .expect("Render error"),
+            "a=1&b=2",
+            "Failed to expose @key to block template"
+        );
+    }
+
+    #[test]
+    fn block_count_and_total() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b"], "obj": {"x": {"name": "c"}}});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat "s" arr obj render_all=true}}{{@count}}/{{@total}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "1/4,2/4,3/4,4/4",
+            "Failed to expose @count and @total to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat "s" arr obj}}{{name}}{{#if @last}} ({{@total}} total){{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "s,a,b,c (1 total)",
+            "Failed to count block template values only"
+        );
+    }
+
+    #[test]
+    fn block_extra() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"users": [{"name": "Alice"}, {"name": "Bob"}]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users class="red" separator="" render_all=true}}<span class="{{@extra_class}}">{{name}}</span>{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#"<span class="red">Alice</span><span class="red">Bob</span>"#,
+            "Failed to forward unknown hash parameters to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users class="red" separator="" render_all=true}}{{lookup @extra "class"}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "redred",
+            "Failed to forward all unknown hash parameters as object"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users ctx_separator=" & " strict=true render_all=true}}{{name}}{{{@extra_separator}}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Alice & ,Bob & ",
+            "Failed to forward prefixed hash parameters to block template"
+        );
+        assert!(
+            h.render_template(
+                r#"{{#concat users class="red" strict=true render_all=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .is_err(),
+            "Failed to reject unknown hash parameters in strict mode"
+        );
+    }
+
+    #[test]
+    fn block_prev_next() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "arr": ["a", "b", "c"],
+            "obj": {"x": {"v": 1}, "y": {"v": 3}, "z": {"v": 6}}
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr render_all=true}}{{#if @prev}}{{@prev}}{{/if}}<{{this}}>{{#if @next}}{{@next}}{{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<a>b,a<b>c,b<c>",
+            "Failed to expose adjacent array items to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj}}{{@key}}:{{#if @prev}}{{lookup @prev "v"}}{{else}}-{{/if}}/{{lookup @next "v"}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "x:-/3,y:1/6,z:3/",
+            "Failed to expose adjacent object values to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj}}{{@key}}{{#if @prev}}+{{@prev_v}}{{/if}}{{#if @next}}-{{@next_v}}{{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "x-3,y+1-6,z+3",
+            "Failed to expose fields of adjacent object values to block template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr item_template="{{this}}{{#if (eq @next \"c\")}}!{{/if}}"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,b!,c",
+            "Failed to expose adjacent array items to item template"
+        );
+
+        for (source, expected) in [
+            ("{{this}} {{#each this}}{{@index}}{{/each}}", false),
+            ("{{#if @first}}{{else}}{{@prev}}{{/if}}", true),
+            ("{{lookup this (concat @next)}}", true),
+            ("{{@prev_name}}", true),
+            ("{{@previous}} {{@nextValue}}", false),
+            ("{{#each this}}{{@../next}}{{/each}}", true),
+            ("{{> item}}", true),
+        ] {
+            let template = handlebars::Template::compile(source).expect("Compile error");
+            assert_eq!(
+                uses_adjacent_locals(&template),
+                expected,
+                "Failed to detect adjacent values in `{}`",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn window() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b", "c", "d"], "short": ["x"]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr window=2 render_all=true separator=", "}}{{this.[0]}}-{{this.[1]}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a-b, b-c, c-d",
+            "Failed to render windows through block template"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr window=3 pair_separator=""}}"#, &data)
+                .expect("Render error"),
+            "abc,bcd",
+            "Failed to join window items"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr window=2 render_all=true}}{{@index}}{{#if @last}}!{{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0,1,2!",
+            "Failed to expose window position to block template"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat short window=2}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to skip arrays shorter than the window"
+        );
+    }
+
+    #[test]
+    fn item_template() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "users": [{"name": "Ann", "email": "ann@example.com"}, {"name": "Bob", "email": "bob@example.com"}],
+            "obj": {"key0": {"name": "Cid"}}
+        });
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat users item_template="{{name}} <{{email}}>" separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Ann <ann@example.com>, Bob <bob@example.com>",
+            "Failed to concat using item template"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#each (concat users obj "Dan" item_template="{{@index}}:{{#if name}}{{name}}{{else}}{{this}}{{/if}}")}}[{{this}}]{{/each}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[0:Ann][1:Bob][0:Cid][:Dan]",
+            "Failed to concat using item template in subexpression"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat users render_all=true item_template="{{email}}"}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Ann,Bob",
+            "Failed to prefer block template over item template"
+        );
+        assert!(
+            h.render_template(r#"{{concat users item_template="{{#if}}"}}"#, &data)
+                .is_err(),
+            "Failed to report invalid item template"
+        );
+    }
+
+    #[test]
+    fn partial() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_partial("item_card", "<{{name}}#{{@index}}>")
+            .expect("Partial error");
+
+        let data = json!({"users": [{"name": "Ann"}, {"name": "Bob"}]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat users partial="item_card" item_template="{{name}}"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<Ann#0>,<Bob#1>",
+            "Failed to concat using registered partial"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#*inline "inline_card"}}[{{name}}]{{/inline}}{{concat users partial="inline_card"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[Ann],[Bob]",
+            "Failed to concat using inline partial"
+        );
+        assert!(
+            h.render_template(r#"{{concat users partial="missing"}}"#, &data)
+                .is_err(),
+            "Failed to report missing partial"
+        );
+    }
+
+    #[test]
+    fn inverse_block() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat items render_all=true}}{{this}}{{else}}none{{/concat}}"#,
+                &json!({"items": []})
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to render inverse block for empty output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat items render_all=true}}{{this}}{{else}}none{{/concat}}"#,
+                &json!({"items": ["a"]})
+            )
+            .expect("Render error"),
+            "a",
+            "Failed to skip inverse block for non-empty output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat missing}}{{this}}{{else}}none{{/concat}}"#,
+                &json!({})
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to render inverse block for missing params"
+        );
+    }
+
+    #[test]
+    fn default_value() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat a b default="N/A"}}"#,
+                &json!({"a": null, "b": []})
+            )
+            .expect("Render error"),
+            "N/A",
+            "Failed to render default for empty output"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a default="N/A"}}"#, &json!({"a": "x"}))
+                .expect("Render error"),
+            "x",
+            "Failed to skip default for non-empty output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat a default="N/A"}}{{this}}{{else}}none{{/concat}}"#,
+                &json!({})
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to prefer inverse block over default"
+        );
+    }
+
+    #[test]
+    fn count() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"tags": ["a", "b", "a", "", "c"], "empty": []});
+
+        assert_eq!(
+            h.render_template(r#"{{concat tags count=true distinct=true}}"#, &data)
+                .expect("Render error"),
+            "4",
+            "Failed to count values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat empty count=true default="N/A"}}"#, &data)
+                .expect("Render error"),
+            "0",
+            "Failed to count empty output"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#if (concat tags count=true contains="z")}}some{{else}}none{{/if}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to count values in subexpression"
+        );
+    }
+
+    #[test]
+    fn subexpression() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": ["x", "y"], "b": "z"});
+
+        assert_eq!(
+            h.render_template(r#"{{#each (concat a b)}}[{{this}}]{{/each}}"#, &data)
+                .expect("Render error"),
+            "[x][y][z]",
+            "Failed to iterate subexpression result"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat (concat a b quotes=true) "w" separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#""x" "y" "z" w"#,
+            "Failed to concat nested subexpression result"
+        );
+    }
+
+    #[test]
+    fn quote_char() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr separator=", " quote_char="`"}}"#,
+                &json!({"arr": ["id", "name"]})
+            )
+            .expect("Render error"),
+            "`id`, `name`",
+            "Failed to concat with custom quotation mark"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quote_char="%%" quotes=true single_quote=true}}"#,
+                &json!({"arr": ["a", "b"]})
+            )
+            .expect("Render error"),
+            "%%a%%,%%b%%",
+            "Failed to prefer custom quotation mark over single quotes"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quote_char="«" quote_end="»" separator=" "}}"#,
+                &json!({"arr": ["a", "b»c"]})
+            )
+            .expect("Render error"),
+            "«a» «b\\»c»",
+            "Failed to concat with distinct closing quotation mark"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quote_char="«" quote_end="»" quote_escape="double"}}"#,
+                &json!({"arr": ["«a»"]})
+            )
+            .expect("Render error"),
+            "««a»»»",
+            "Failed to double distinct closing quotation mark"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quote_end="»" quotes=true}}"#,
+                &json!({"arr": ["a"]})
+            )
+            .expect("Render error"),
+            "\"a\"",
+            "Failed to ignore closing quotation mark without custom opening one"
+        );
+    }
+
+    #[test]
+    fn json_encode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"arr": ["say \"hi\"", "back\\slash", "line\nbreak\ttab\u{1}", "Über\u{2028}"]});
+
+        let output = h
+            .render_template(r#"[{{concat arr json_encode=true quotes=true}}]"#, &data)
+            .expect("Render error");
+        assert_eq!(
+            output, r#"["say \"hi\"","back\\slash","line\nbreak\ttab\u0001","Über\u2028"]"#,
+            "Failed to encode values as JSON string literals"
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output).expect("Parse error"),
+            data["arr"],
+            "Failed to produce valid JSON"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr json_encode=true separator=", " limit=1 render_all=true}}<{{this}}>{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#""<say &quot;hi&quot;>""#,
+            "Failed to encode block template output"
+        );
+    }
+
+    #[test]
+    fn quote_strings() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": [1, "two", 3.5, true, null, "x y", -2e3]});
+
+        let output = h
+            .render_template(
+                r#"[{{concat arr quotes="strings" null="null" separator=", "}}]"#,
+                &data,
+            )
+            .expect("Render error");
+        assert_eq!(
+            output, r#"[1, "two", 3.5, true, null, "x y", -2000.0]"#,
+            "Failed to quote string values only"
+        );
+        assert!(
+            serde_json::from_str::<serde_json::Value>(&output).is_ok(),
+            "Failed to produce valid JSON: {}",
+            output
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes="strings" single_quote=true thousands_separator="," precision=1}}"#,
+                &json!({"arr": [12, 1234, "it's"]})
+            )
+            .expect("Render error"),
+            "12.0,'1,234.0','it\\'s'",
+            "Failed to quote formatted numbers which are no longer valid numbers"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes="strings" null="null"}}"#,
+                &json!({"arr": ["1", 2, "two", "null", true, "true", null]})
+            )
+            .expect("Render error"),
+            r#""1",2,"two","null",true,"true",null"#,
+            "Failed to quote strings which look like other types"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr obj quotes="strings" mode="values" bool_true="yes"}}"#,
+                &json!({"arr": [true, false], "obj": {"a": 1, "b": "1"}})
+            )
+            .expect("Render error"),
+            r#""yes",false,1,"1""#,
+            "Failed to quote object values and replaced booleans by their type"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr quotes=true limit=2}}"#, &data)
+                .expect("Render error"),
+            r#""1","two""#,
+            "Failed to quote all values"
+        );
+    }
+
+    #[test]
+    fn quote_auto() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"arr": ["plain", "with space", "a,b", "say \"hi\"", "tab\there", 42, ""]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr quotes="auto"}}"#, &data)
+                .expect("Render error"),
+            r#"plain,"with space","a,b","say \"hi\"","tab	here",42,"#,
+            "Failed to quote values that need it"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes="auto" separator=";" quote_escape="double" limit=4}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#"plain;"with space";a,b;"say ""hi""""#,
+            "Failed to quote values containing custom separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes="auto" quote_char="'" limit=4}}"#,
+                &json!({"arr": ["it's", "x\"y"]})
+            )
+            .expect("Render error"),
+            r#"'it\'s',x"y"#,
+            "Failed to quote values containing custom quotation mark"
+        );
+    }
+
+    #[test]
+    fn quote_identifiers() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"columns": ["id", "user name", "odd`name", "odd]name", "[x]"]});
+
+        assert_eq!(
+            h.render_template(
+                r#"SELECT {{concat columns quotes="backtick" separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "SELECT `id`, `user name`, `odd``name`, `odd]name`, `[x]`",
+            "Failed to quote identifiers in backticks"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"SELECT {{concat columns quotes="bracket" quote_char="'" separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "SELECT [id], [user name], [odd`name], [odd]]name], [[x]]]",
+            "Failed to quote identifiers in brackets"
+        );
+    }
+
+    #[test]
+    fn quote_escape() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": [r#"say "hi""#, r#"back\slash"#, "it's"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr quotes=true}}"#, &data)
+                .expect("Render error"),
+            r#""say \"hi\"","back\\slash","it's""#,
+            "Failed to escape quotation marks with backslash"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes=true single_quote=true quote_escape="double"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#"'say "hi"','back\slash','it''s'"#,
+            "Failed to escape quotation marks by doubling"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr quotes=true quote_escape="none"}}"#, &data)
+                .expect("Render error"),
+            r#""say "hi"","back\slash","it's""#,
+            "Failed to skip escaping quotation marks"
+        );
+    }
+
+    #[test]
+    fn escape_html() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["<b>", "a&b"], "obj": {"key0": {"name": "<i>"}}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr escape="html" prefix="<li>"}}"#, &data)
+                .expect("Render error"),
+            "<li>&lt;b&gt;,<li>a&amp;b",
+            "Failed to HTML-escape values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj escape="html"}}{{{name}}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "&lt;i&gt;",
+            "Failed to HTML-escape block template output"
+        );
+    }
+
+    #[test]
+    fn escape_output() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_helper(
+            "safe_concat",
+            Box::new(HandlebarsConcat::new().with_escape_output(true)),
+        );
+
+        let data = json!({"arr": ["<a>", "b&c"], "empty": []});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr escape_output=true separator="<br>"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "&lt;a&gt;&lt;br&gt;b&amp;c",
+            "Failed to escape output"
+        );
+        assert_eq!(
+            h.render_template(r#"{{safe_concat empty default="<none>"}}"#, &data)
+                .expect("Render error"),
+            "&lt;none&gt;",
+            "Failed to escape default value"
+        );
+        assert_eq!(
+            h.render_template(r#"{{safe_concat arr escape_output=false}}"#, &data)
+                .expect("Render error"),
+            "<a>,b&c",
+            "Failed to disable registered output escaping"
+        );
+
+        h.register_escape_fn(|s| s.to_uppercase());
+
+        assert_eq!(
+            h.render_template(r#"{{safe_concat arr}}"#, &data)
+                .expect("Render error"),
+            "<A>,B&C",
+            "Failed to escape output through custom escape function"
+        );
+    }
+
+    #[test]
+    fn trim() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": [" foo ", "bar", "foo", "  "], "obj": {"key0": {"name": "bar"}}});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr trim=true distinct=true separator=", "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "foo, bar",
+            "Failed to trim values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj trim=true}}
+    {{name}}
+{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "bar",
+            "Failed to trim block template output"
+        );
+    }
+
+    #[test]
+    fn case() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data =
+            json!({"tags": ["Rust", "rust", "RUST", "web"], "obj": {"key0": {"name": "ärger"}}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat tags case="lower" distinct=true}}"#, &data)
+                .expect("Render error"),
+            "rust,web",
+            "Failed to concat lower case values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat tags case="upper"}}"#, &data)
+                .expect("Render error"),
+            "RUST,RUST,RUST,WEB",
+            "Failed to concat upper case values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat tags obj case="capitalize" render_all=true}}{{#if name}}{{name}}{{else}}{{this}}{{/if}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Rust,Rust,RUST,Web,Ärger",
+            "Failed to concat capitalized block template output"
+        );
+    }
+
+    #[test]
+    fn slugify() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"labels": ["Hello, World!", " hello   world ", "C++ & Rust_lang", "--", "Über 9000"]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat labels slugify=true trim=true separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "hello-world hello-world c-rust-lang über-9000",
+            "Failed to concat slugified values"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat labels slugify=true trim=true distinct=true separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "hello-world c-rust-lang über-9000",
+            "Failed to eliminate duplicate slugs"
+        );
+    }
+
+    #[test]
+    fn feature_params() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", "b"]});
+
+        for (name, enabled, _) in FEATURE_PARAMS {
+            let result = h.render_template(&format!(r#"{{{{concat arr {}="a"}}}}"#, name), &data);
+
+            // the value may still be invalid, e.g. as script, as long as the parameter itself is accepted
+            if *enabled {
+                assert!(
+                    !matches!(
+                        result.as_ref().map_err(ConcatError::from_render_error),
+                        Err(Some(ConcatError::InvalidHashParam { reason, .. })) if reason.starts_with("requires")
+                    ),
+                    "Failed to accept {}: {:?}",
+                    name,
+                    result
+                );
+            } else {
+                let error = result.expect_err("Failed to reject parameter of disabled feature");
+                assert!(
+                    matches!(
+                        ConcatError::from_render_error(&error),
+                        Some(ConcatError::InvalidHashParam { name: param, .. }) if param == name
+                    ),
+                    "Failed to reject {} without its feature: {:?}",
+                    name,
+                    error
+                );
+                assert!(
+                    h.render_template(&format!(r#"{{{{concat arr {}=false}}}}"#, name), &data)
+                        .is_ok(),
+                    "Failed to accept disabled {} without its feature",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn trim_block() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": [{"n": "a", "show": true}, {"n": "b"}, {"n": "c", "show": true}], "s": " x "});
+
+        let template = "{{#concat arr s separator=\", \" render_all=true %s}}\n  {{#if show}}{{n}}{{/if}}\n{{/concat}}";
+
+        assert_eq!(
+            h.render_template(&template.replace("%s", "trim_block=true"), &data)
+                .expect("Render error"),
+            "a, c",
+            "Failed to skip whitespace only block output"
+        );
+        assert_eq!(
+            h.render_template(
+                &template.replace("%s", "trim_block=true keep_empty=true"),
+                &data
+            )
+            .expect("Render error"),
+            "a, c",
+            "Failed to skip whitespace only block output with keep_empty"
+        );
+        assert_eq!(
+            h.render_template(&template.replace("%s", ""), &data)
+                .expect("Render error"),
+            "  a\n,   \n,   c\n,   \n",
+            "Failed to keep block output without trim_block"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr s separator="|" trim_block=true}}{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "[object]|[object]|[object]| x ",
+            "Failed to leave values outside of the block template untrimmed"
+        );
+
+        #[cfg(all(feature = "rayon", feature = "handlebars6"))]
+        assert_eq!(
+            h.render_template(
+                &template.replace("%s", "trim_block=true parallel=true"),
+                &data
+            )
+            .expect("Render error"),
+            "a, c",
+            "Failed to skip whitespace only block output rendered in parallel"
+        );
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn normalize() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        // composed and decomposed forms of the same accented letters
+        let data = json!({"names": ["Caf\u{e9}", "Cafe\u{301}", "Zo\u{eb}", "Zoe\u{308}"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat names distinct=true}}"#, &data)
+                .expect("Render error"),
+            "Caf\u{e9},Cafe\u{301},Zo\u{eb},Zoe\u{308}",
+            "Failed to keep differently encoded values without normalization"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat names distinct=true normalize=true}}"#, &data)
+                .expect("Render error"),
+            "Caf\u{e9},Zo\u{eb}",
+            "Failed to eliminate normalized duplicates"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat names distinct="ci" normalize=true}}{{this}}{{/concat}}"#,
+                &json!({"names": ["\u{c9}t\u{e9}", "e\u{301}te\u{301}"]})
+            )
+            .expect("Render error"),
+            "\u{c9}t\u{e9}",
+            "Failed to eliminate normalized block output case-insensitively"
+        );
+    }
+
+    #[test]
+    fn keep_empty() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": "x", "b": "", "c": "z", "arr": ["", "y"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat a b c keep_empty=true}}"#, &data)
+                .expect("Render error"),
+            "x,,z",
+            "Failed to keep empty values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a b c arr}}"#, &data)
+                .expect("Render error"),
+            "x,z,,y",
+            "Failed to skip empty strings, keeping empty array items"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a b c arr trim=true}}"#, &data)
+                .expect("Render error"),
+            "x,z,y",
+            "Failed to skip empty array items with trim"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr obj}}"#,
+                &json!({"arr": ["a", "", "b"], "obj": {"": 1, "c": 2}})
+            )
+            .expect("Render error"),
+            "a,,b,,c",
+            "Failed to keep empty array items and object keys"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr b keep_empty=true distinct=true}}"#, &data)
+                .expect("Render error"),
+            ",y",
+            "Failed to keep empty values with distinct"
+        );
+    }
+
+    #[test]
+    fn null_placeholder() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": "x", "b": null, "arr": [1, null, 3]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat a b arr null="-"}}"#, &data)
+                .expect("Render error"),
+            "x,-,1,-,3",
+            "Failed to render null placeholder"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a b arr}}"#, &data)
+                .expect("Render error"),
+            "x,1,,3",
+            "Failed to skip null params, keeping null array items"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr quotes=true}}"#,
+                &json!({"arr": ["a", "", "b", null, "c"]})
+            )
+            .expect("Render error"),
+            r#""a","","b","","c""#,
+            "Failed to quote empty and null array items"
+        );
+    }
+
+    #[test]
+    fn bool_format() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": true, "arr": [false, true]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat a arr bool_true="yes" bool_false="no"}}"#, &data)
+                .expect("Render error"),
+            "yes,no,yes",
+            "Failed to render custom booleans"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a arr bool_true="1"}}"#, &data)
+                .expect("Render error"),
+            "1,false,1",
+            "Failed to render partially customized booleans"
+        );
+    }
+
+    #[test]
+    fn number_format() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"n": 1234.5, "arr": [-1234567, 0.125, 7]});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat n arr precision=2 thousands_separator="," separator="; "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "1,234.50; -1,234,567.00; 0.12; 7.00",
+            "Failed to format numbers with precision and thousands separator"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr leading_zeros=3}}"#, &data)
+                .expect("Render error"),
+            "-1234567,000.125,007",
+            "Failed to format numbers with leading zeros"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat n arr}}"#, &data)
+                .expect("Render error"),
+            "1234.5,-1234567,0.125,7",
+            "Failed to render numbers without formatting"
+        );
+    }
+
+    #[test]
+    fn flatten() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["a", ["b", ["c", ["d"]]], "a"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr flatten=true distinct=true}}"#, &data)
+                .expect("Render error"),
+            "a,b,c,d",
+            "Failed to flatten all levels"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr flatten=1 separator="|"}}"#, &data)
+                .expect("Render error"),
+            "a|b|[c, [d]]|a",
+            "Failed to flatten specific number of levels"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat arr flatten=true render_all=true}}{{@index}}{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0a,1b,2c,3d,4a",
+            "Failed to flatten arrays rendered through block template"
+        );
+    }
+
+    #[test]
+    fn recursive() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "obj": {"a": 1, "b": {"c": "two", "d": [3, {"e": "four"}]}},
+            "arr": [{"x": "five"}, ["six"], 7]
+        });
+
+        assert_eq!(
+            h.render_template(r#"{{concat obj arr recursive=true}}"#, &data)
+                .expect("Render error"),
+            "1,two,3,four,five,six,7",
+            "Failed to collect nested scalar values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj}}"#, &data)
+                .expect("Render error"),
+            "a,b",
+            "Failed to keep object keys without recursive mode"
+        );
+    }
+
+    #[test]
+    fn current_context() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": 1, "b": 2, "list": [["x", "y"], ["z"]], "obj": {"c": 3}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat separator=", "}}"#, &data)
+                .expect("Render error"),
+            "a, b, list, obj",
+            "Failed to concat root context without params"
+        );
+        assert_eq!(
+            h.render_template(r#"{{#each list}}[{{concat}}]{{/each}}"#, &data)
+                .expect("Render error"),
+            "[x,y][z]",
+            "Failed to concat current block context without params"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#with obj}}{{#concat}}{{@key}}={{this}}{{/concat}}{{/with}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "c=3",
+            "Failed to render block template for current context without params"
+        );
+    }
+
+    #[test]
+    fn pointer() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "a": {"results": {"items": ["One", "Two"]}},
+            "b": {"results": {"items": ["Three"]}},
+            "c": {"results": {}},
+            "d": {"results": {"items": [{"name": "Four"}, {"name": "Five"}]}}
+        });
+
+        assert_eq!(
+            h.render_template(r#"{{concat a b c pointer="/results/items"}}"#, &data)
+                .expect("Render error"),
+            "One,Two,Three",
+            "Failed to descend into params through pointer"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat d pointer="/results/items" render_all=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Four,Five",
+            "Failed to render block template for items reached through pointer"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat d pointer="/results/items/1/name"}}"#, &data)
+                .expect("Render error"),
+            "Five",
+            "Failed to descend into array items through pointer"
+        );
+        assert!(
+            h.render_template(
+                r#"{{concat c pointer="/results/items" strict=true}}"#,
+                &data
+            )
+            .is_err(),
+            "Failed to reject unresolved pointer in strict mode"
+        );
+        assert!(
+            h.render_template(r#"{{concat a pointer="results"}}"#, &data)
+                .is_err(),
+            "Failed to reject invalid pointer"
+        );
+    }
+
+    #[test]
+    fn pluck() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({
+            "users": [{"email": "a@x"}, {"name": "b"}, {"email": "c@x"}],
+            "obj": {"key0": {"meta": {"email": "d@x"}}}
+        });
+
+        assert_eq!(
+            h.render_template(r#"{{concat users pluck="email" separator="; "}}"#, &data)
+                .expect("Render error"),
+            "a@x; c@x",
+            "Failed to pluck field from array items"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj pluck="meta.email"}}"#, &data)
+                .expect("Render error"),
+            "d@x",
+            "Failed to pluck nested field from object values"
+        );
+    }
+
+    #[test]
+    fn object_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"obj": {"a": 1, "b": "two", "c": null}});
+
+        assert_eq!(
+            h.render_template(r#"{{concat obj mode="keys"}}"#, &data)
+                .expect("Render error"),
+            "a,b,c",
+            "Failed to concat object keys"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj mode="values"}}"#, &data)
+                .expect("Render error"),
+            "1,two",
+            "Failed to concat object values"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj mode="entries" separator="&"}}"#, &data)
+                .expect("Render error"),
+            "a=1&b=two",
+            "Failed to concat object entries"
+        );
+    }
+
+    #[test]
+    fn html_list_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_helper(
+            "concat_values",
+            Box::new(HandlebarsConcat::new().with_default("mode", "values")),
+        );
+
+        let data = json!({"arr": ["a", "<b>"], "obj": {"x": {"name": "<y>"}, "z": {"name": "w"}}, "flat": {"p": 1, "q": "<b>"}, "empty": []});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr mode="ul" separator=", "}}"#, &data)
+                .expect("Render error"),
+            "<ul><li>a</li><li>&lt;b&gt;</li></ul>",
+            "Failed to concat as unordered list"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj mode="ol"}}"#, &data)
+                .expect("Render error"),
+            "<ol><li>x</li><li>z</li></ol>",
+            "Failed to concat as ordered list"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj mode="ul"}}<i>{{name}}</i>{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<ul><li><i>&lt;y&gt;</i></li><li><i>w</i></li></ul>",
+            "Failed to concat block template output as list"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr flat mode="values ol"}}"#, &data)
+                .expect("Render error"),
+            "<ol><li>a</li><li>&lt;b&gt;</li><li>1</li><li>&lt;b&gt;</li></ol>",
+            "Failed to combine object and list modes"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat_values flat mode="ul"}}"#, &data)
+                .expect("Render error"),
+            "<ul><li>1</li><li>&lt;b&gt;</li></ul>",
+            "Failed to combine registered object mode and list mode"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat empty mode="ul"}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to skip empty list"
+        );
+    }
+
+    #[test]
+    fn markdown_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"arr": ["Fixed *bug*", "Added feature"], "empty": []});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr mode="markdown" separator=", "}}"#, &data)
+                .expect("Render error"),
+            "- Fixed *bug*\n- Added feature",
+            "Failed to concat as Markdown list"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr mode="markdown" ordered=true}}"#, &data)
+                .expect("Render error"),
+            "1. Fixed *bug*\n2. Added feature",
+            "Failed to concat as ordered Markdown list"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat empty mode="markdown"}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to skip empty Markdown list"
+        );
+    }
+
+    #[test]
+    fn csv_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"row": ["plain", "a,b", "say \"hi\"", "two\nlines", 42]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat row mode="csv"}}"#, &data)
+                .expect("Render error"),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",42",
+            "Failed to concat as CSV"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat row mode="csv" separator=";"}}"#, &data)
+                .expect("Render error"),
+            "plain;a,b;\"say \"\"hi\"\"\";\"two\nlines\";42",
+            "Failed to concat as CSV with custom delimiter"
+        );
+
+        for (name, param) in [
+            ("quotes", "quotes=true"),
+            ("quote_char", "quote_char=\"'\""),
+            ("chunk", "chunk=2"),
+        ] {
+            let error = h
+                .render_template(
+                    &format!(r#"{{{{concat row mode="csv" {}}}}}"#, param),
+                    &data,
+                )
+                .expect_err("Failed to reject param with CSV");
+            assert!(
+                matches!(
+                    ConcatError::from_render_error(&error),
+                    Some(ConcatError::InvalidHashParam { name: invalid, .. }) if *invalid == name
+                ),
+                "Failed to reject `{}` with CSV: {:?}",
+                name,
+                error
+            );
+        }
+        assert_eq!(
+            h.render_template(r#"{{concat row mode="csv" quotes=false}}"#, &data)
+                .expect("Render error"),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",42",
+            "Failed to accept disabled quotes with CSV"
+        );
+    }
+
+    #[test]
+    fn sql_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"ids": ["a", "O'Brien", 3, 1.5, "007", "1; DROP TABLE users"], "strings": ["42", 42, "\\' OR 1=1 --"], "empty": []});
+
+        assert_eq!(
+            h.render_template(r#"IN ({{concat ids mode="sql"}})"#, &data)
+                .expect("Render error"),
+            "IN ('a','O''Brien',3,1.5,'007','1; DROP TABLE users')",
+            "Failed to concat as SQL literals"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat empty mode="sql" wrap_start="IN (" wrap_end=")" default="IS NULL"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "IS NULL",
+            "Failed to concat empty SQL list"
+        );
+        assert_eq!(
+            h.render_template(r#"IN ({{concat strings mode="sql"}})"#, &data)
+                .expect("Render error"),
+            r#"IN ('42',42,'\'' OR 1=1 --')"#,
+            "Failed to quote strings which look like numbers"
+        );
+    }
+
+    #[test]
+    fn path_mode() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"base": "/opt/", "dirs": ["/app//", "config"], "file": "app.toml"});
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat base dirs file mode="path" separator="/"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "/opt/app/config/app.toml",
+            "Failed to concat as path"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat "a" "b" mode="path"}}"#, &data)
+                .expect("Render error"),
+            format!("a{}b", std::path::MAIN_SEPARATOR),
+            "Failed to concat as path with platform separator"
+        );
+    }
+
+    #[test]
+    fn key_value_separator() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        assert_eq!(
+            h.render_template(
+                r#"{{concat obj mode="entries" key_value_separator=": " separator="; "}}"#,
+                &json!({"obj": {"a": 1, "b": 2}})
+            )
+            .expect("Render error"),
+            "a: 1; b: 2",
+            "Failed to concat object entries with key value separator"
+        );
+    }
 
-        // enable quotation marks wrapping
-        let quotes = h.hash_get("quotes").is_some();
+    #[test]
+    fn filter_keys() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-        // as a modifier on top of "quotes", switches to single quotation
-        let single_quote = h.hash_get("single_quote").is_some();
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
 
-        let wrapper = if quotes {
-            if single_quote {
-                QUOTES_SINGLE
-            } else {
-                QUOTES_DOUBLE
-            }
-        } else {
-            ""
-        };
+        let data = json!({"headers": {"x-id": "1", "x-trace-id": "2", "accept": "*/*"}});
 
-        let render_all = h.hash_get("render_all").is_some(); // force all values through the block template
+        assert_eq!(
+            h.render_template(r#"{{concat headers filter_key_prefix="x-"}}"#, &data)
+                .expect("Render error"),
+            "x-id,x-trace-id",
+            "Failed to filter object keys by prefix"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat headers filter_key_suffix="-id" filter_key_prefix="x-t"}}{{this}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "2",
+            "Failed to filter object values by key prefix and suffix"
+        );
+    }
 
-        let template = h.template();
+    #[test]
+    fn filter_where() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-        let mut output: Vec<String> = Vec::new();
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
 
-        for param in h.params() {
-            match param.value() {
-                serde_json::Value::Null => {}
-                serde_json::Value::Bool(_)
-                | serde_json::Value::Number(_)
-                | serde_json::Value::String(_) => {
-                    let value = if h.is_block() && render_all {
-                        // use block template to render strings
+        let data = json!({
+            "items": [
+                {"name": "a", "status": "active"},
+                {"name": "b", "status": "inactive"},
+                {"name": "c", "status": "active"},
+                {"name": "d"}
+            ],
+            "obj": {"key0": {"name": "e", "meta": {"level": 1}}, "key1": {"name": "f", "meta": {"level": 2}}}
+        });
 
-                        let mut content = StringOutput::default();
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat items where="status=active" render_all=true}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a,c",
+            "Failed to filter array items by field"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat obj where="meta.level=2"}}"#, &data)
+                .expect("Render error"),
+            "key1",
+            "Failed to filter object entries by nested field"
+        );
+    }
 
-                        rc.push_block(create_block(&param));
-                        template
-                            .map(|t| t.render(r, ctx, rc, &mut content))
-                            .unwrap_or(Ok(()))?;
-                        rc.pop_block();
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_filters() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-                        content.into_string().unwrap_or_default()
-                    } else {
-                        param.value().render()
-                    };
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
 
-                    let value = apply_wrapper(value, wrapper, quotes);
+        let data = json!({"arr": ["ID-1", "note", "ID-2", "id-3", "ID-x"]});
 
-                    if !value.is_empty() && (!output.contains(&value) || !distinct) {
-                        output.push(value);
-                    }
-                }
-                serde_json::Value::Array(ar) => {
-                    if h.is_block() && render_all {
-                        // use block template to render array elements
+        assert_eq!(
+            h.render_template(r#"{{concat arr match="^ID-"}}"#, &data)
+                .expect("Render error"),
+            "ID-1,ID-2,ID-x",
+            "Failed to filter values matching pattern"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat arr match="^ID-" exclude_match="x$"}}"#, &data)
+                .expect("Render error"),
+            "ID-1,ID-2",
+            "Failed to filter values excluding pattern"
+        );
+        assert!(
+            h.render_template(r#"{{concat arr match="("}}"#, &data)
+                .is_err(),
+            "Failed to report invalid pattern"
+        );
+    }
 
-                        for array_item in ar {
-                            let mut content = StringOutput::default();
+    #[cfg(feature = "rhai")]
+    #[test]
+    fn script_filters() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-                            let block = create_block(&PathAndJson::new(
-                                None,
-                                ScopedJson::from(array_item.clone()),
-                            ));
-                            rc.push_block(block);
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
 
-                            template
-                                .map(|t| t.render(r, ctx, rc, &mut content))
-                                .unwrap_or(Ok(()))?;
+        let data = json!({"arr": ["kiwi", "fig", "banana", "apple"]});
 
-                            rc.pop_block();
+        assert_eq!(
+            h.render_template(r#"{{concat arr filter_script="value.len() > 3"}}"#, &data)
+                .expect("Render error"),
+            "kiwi,banana,apple",
+            "Failed to filter values through script"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat arr sort_script="a.len() - b.len()" sort="desc"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "fig,kiwi,apple,banana",
+            "Failed to sort values through script"
+        );
+        assert!(
+            h.render_template(r#"{{concat arr filter_script="value.len("}}"#, &data)
+                .is_err(),
+            "Failed to report invalid script"
+        );
+        assert!(
+            h.render_template(r#"{{concat arr sort_script="a < b"}}"#, &data)
+                .is_err(),
+            "Failed to report script with unexpected result"
+        );
+    }
 
-                            if let Ok(value) = content.into_string() {
-                                let value = apply_wrapper(value, wrapper, quotes);
+    #[cfg(all(feature = "rayon", feature = "handlebars6"))]
+    #[test]
+    fn parallel() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-                                if !value.is_empty() && (!output.contains(&value) || !distinct) {
-                                    output.push(value);
-                                }
-                            }
-                        }
-                    } else {
-                        output.append(
-                            &mut ar
-                                .iter()
-                                .map(|item| item.render())
-                                .map(|item| apply_wrapper(item, wrapper, quotes))
-                                .filter(|item| {
-                                    if distinct {
-                                        !output.contains(item)
-                                    } else {
-                                        true
-                                    }
-                                })
-                                .collect::<Vec<String>>(),
-                        );
-                    }
-                }
-                serde_json::Value::Object(o) => {
-                    if h.is_block() {
-                        // use block template to render objects
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+        h.register_partial("item", "<{{name}}>")
+            .expect("Partial error");
 
-                        for obj in o.values() {
-                            let mut content = StringOutput::default();
+        let data = json!({
+            "title": "T",
+            "users": (0..100).map(|i| json!({"name": format!("user{}", i)})).collect::<Vec<_>>()
+        });
 
-                            let block = create_block(&PathAndJson::new(
-                                None,
-                                ScopedJson::from(obj.clone()),
-                            ));
-                            rc.push_block(block);
+        for template in [
+            r#"{{#concat users render_all=true}}{{@index}}:{{name}}/{{@count}}/{{@total}}{{#if @last}}!{{/if}}{{/concat}}"#,
+            r#"{{#with this as |root|}}{{#concat users "s" render_all=true}}{{#if name}}{{root.title}}{{name}}{{else}}{{this}}{{/if}}{{/concat}}{{/with}}"#,
+            r#"{{concat users item_template="{{name}}" distinct=true}}"#,
+            r#"{{#concat users render_all=true}}{{> item}}{{/concat}}"#,
+        ] {
+            let parallel = template
+                .replacen("render_all=true", "render_all=true parallel=true", 1)
+                .replacen("distinct=true", "distinct=true parallel=true", 1);
 
-                            template
-                                .map(|t| t.render(r, ctx, rc, &mut content))
-                                .unwrap_or(Ok(()))?;
+            assert_eq!(
+                h.render_template(&parallel, &data).expect("Render error"),
+                h.render_template(template, &data).expect("Render error"),
+                "Failed to render block template in parallel"
+            );
+        }
 
-                            rc.pop_block();
+        assert!(
+            h.render_template(
+                r#"{{#concat users render_all=true parallel=true}}{{#if}}{{/if}}{{/concat}}"#,
+                &data
+            )
+            .is_err(),
+            "Failed to report render error in parallel"
+        );
+    }
 
-                            if let Ok(value) = content.into_string() {
-                                let value = apply_wrapper(value, wrapper, quotes);
+    #[test]
+    fn substring_filters() {
+        use handlebars::Handlebars;
+        use serde_json::json;
 
-                                if !value.is_empty() && (!output.contains(&value) || !distinct) {
-                                    output.push(value);
-                                }
-                            }
-                        }
-                    } else {
-                        // render keys only
-
-                        output.append(
-                            &mut o
-                                .keys()
-                                .cloned()
-                                .map(|item| apply_wrapper(item, wrapper, quotes))
-                                .filter(|item| {
-                                    if distinct {
-                                        !output.contains(item)
-                                    } else {
-                                        true
-                                    }
-                                })
-                                .collect::<Vec<String>>(),
-                        );
-                    }
-                }
-            }
-        }
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
 
-        out.write(&output.join(&*separator))?;
+        let data = json!({
+            "tags": ["lang-rust", "lang-go", "os-linux", "lang-rust-async"],
+            "obj": {"key0": {"name": "lang-c"}, "key1": {"name": "os-mac"}}
+        });
 
-        Ok(())
+        assert_eq!(
+            h.render_template(r#"{{concat tags starts_with="lang-"}}"#, &data)
+                .expect("Render error"),
+            "lang-rust,lang-go,lang-rust-async",
+            "Failed to filter values by prefix"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat tags contains="rust" ends_with="async"}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "lang-rust-async",
+            "Failed to filter values by substring and suffix"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat obj starts_with="lang-"}}{{name}}{{/concat}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "lang-c",
+            "Failed to filter block template output"
+        );
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn it_works() {
+    fn range_filters() {
         use handlebars::Handlebars;
         use serde_json::json;
 
         let mut h = Handlebars::new();
         h.register_helper("concat", Box::new(HandlebarsConcat));
 
+        let data = json!({
+            "scores": [10, 50, 75.5, "90", "n/a"],
+            "users": [{"age": 17}, {"age": 18}, {"age": 30}]
+        });
+
         assert_eq!(
-            h.render_template(r#"{{concat 1 2}}"#, &String::new())
+            h.render_template(r#"{{concat scores gt=50}}"#, &data)
                 .expect("Render error"),
-            "1,2",
-            "Failed to concat numeric literals"
+            "75.5,90",
+            "Failed to filter values greater than bound"
         );
         assert_eq!(
-            h.render_template(r#"{{concat "One" "Two"}}"#, &String::new())
+            h.render_template(r#"{{concat scores gte=50 lt="90"}}"#, &data)
                 .expect("Render error"),
-            "One,Two",
-            "Failed to concat literals"
+            "50,75.5",
+            "Failed to filter values within range"
         );
         assert_eq!(
-            h.render_template(r#"{{concat "One" "Two" separator=", "}}"#, &String::new())
+            h.render_template(r#"{{concat users pluck="age" gte=18 lte=29}}"#, &data)
                 .expect("Render error"),
-            "One, Two",
-            "Failed to concat literals with separator"
+            "18",
+            "Failed to filter plucked values within range"
         );
+    }
+
+    #[test]
+    fn strict() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": "x", "b": null});
+
         assert_eq!(
-            h.render_template(
-                r#"{{concat "One" "Two" separator=", " quotes=true}}"#,
-                &String::new()
-            )
-            .expect("Render error"),
-            r#""One", "Two""#,
-            "Failed to concat literals with separator and quotes"
+            h.render_template(r#"{{concat a b strict=true}}"#, &data)
+                .expect("Render error"),
+            "x",
+            "Failed to accept null params in strict mode"
+        );
+        let error = h
+            .render_template(r#"{{concat a typo strict=true}}"#, &data)
+            .expect_err("Failed to report missing param in strict mode");
+        assert!(
+            error.to_string().contains(r#"param "typo" at index 1"#),
+            "Failed to describe missing param: {}",
+            error
         );
         assert_eq!(
-            h.render_template(
-                r#"{{concat "One" "Two" separator=", " quotes=true single_quote=true}}"#,
-                &String::new()
-            )
-            .expect("Render error"),
-            r#"'One', 'Two'"#,
-            "Failed to concat literals with separator and single quotation marks"
+            h.render_template(r#"{{concat a typo}}"#, &data)
+                .expect("Render error"),
+            "x",
+            "Failed to skip missing param"
+        );
+    }
+
+    #[test]
+    fn strict_hash() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(HandlebarsConcat));
+
+        let data = json!({"a": "x", "b": "y"});
+
+        let error = h
+            .render_template(r#"{{concat a b seperator=", " strict=true}}"#, &data)
+            .expect_err("Failed to report unknown hash parameter in strict mode");
+        assert!(
+            error
+                .to_string()
+                .contains("unknown hash parameters seperator")
+                && error.to_string().contains("separator"),
+            "Failed to describe unknown hash parameter: {}",
+            error
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat a b seperator=", "}}"#, &data)
+                .expect("Render error"),
+            "x,y",
+            "Failed to ignore unknown hash parameter"
         );
         assert_eq!(
             h.render_template(
-                r#"{{concat s arr obj separator=", " quotes=true}}"#,
-                &json!({"arr": ["One", "Two", "Three"]})
+                r#"{{concat a b separator=", " last_separator=" and " strict=true}}"#,
+                &data
             )
             .expect("Render error"),
-            r#""One", "Two", "Three""#,
-            "Failed to concat array with quotes"
+            "x and y",
+            "Failed to accept known hash parameters in strict mode"
+        );
+    }
+
+    #[test]
+    fn registration_defaults() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper(
+            "concat",
+            Box::new(
+                HandlebarsConcat::new()
+                    .with_separator(", ")
+                    .with_distinct(true)
+                    .with_quotes('`')
+                    .with_default("sort", "desc"),
+            ),
+        );
+
+        let data = json!({"arr": ["a", "c", "b", "a"]});
+
+        assert_eq!(
+            h.render_template(r#"{{concat arr}}"#, &data)
+                .expect("Render error"),
+            "`c`, `b`, `a`",
+            "Failed to apply registration time defaults"
         );
         assert_eq!(
             h.render_template(
-                r#"{{concat s arr obj separator=", " distinct=true}}"#,
-                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"Three":3}})
+                r#"{{concat arr separator="|" sort="asc" distinct=false quotes=true single_quote=true}}"#,
+                &data
             )
             .expect("Render error"),
-            "One, Two, Three",
-            "Failed to concat literal, array and object"
+            "'a'|'a'|'b'|'c'",
+            "Failed to override registration time defaults"
         );
         assert_eq!(
-            h.render_template(
-                r#"{{#concat s arr obj separator=", " distinct=true}}{{label}}{{/concat}}"#,
-                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            "One, Two, Three, Four",
-            "Failed to concat literal, array and object using block template"
+            h.render_template(r#"{{concat arr quotes=false}}"#, &data)
+                .expect("Render error"),
+            "c, b, a",
+            "Failed to disable registration time quotation mark"
         );
-        assert_eq!(
-            h.render_template(
-                r#"{{#concat s arr obj separator=", " distinct=true quotes=true}}{{label}}{{/concat}}"#,
-                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            r#""One", "Two", "Three", "Four""#,
-            "Failed to concat literal, array and object using block template"
+
+        let mut h = Handlebars::new();
+        h.register_helper(
+            "concat",
+            Box::new(HandlebarsConcat::new().with_strict(true)),
         );
+
+        assert!(
+            h.render_template(r#"{{concat arr seperator=", "}}"#, &data)
+                .is_err(),
+            "Failed to apply registration time strict mode"
+        );
+    }
+
+    #[test]
+    fn register_helpers() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        register_concat_helpers(&mut h);
+
         assert_eq!(
-            h.render_template(
-                r#"{{concat obj separator=", " quotes=true}}"#,
-                &json!({"obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            r#""key0", "key1", "key2""#,
-            "Failed to concat object keys with quotation marks and no distinction"
+            h.render_template(r#"{{concat arr}}"#, &json!({"arr": ["a", "b"]}))
+                .expect("Render error"),
+            "a,b",
+            "Failed to render registered concat helper"
         );
+    }
+
+    #[test]
+    fn object_mode_variants() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        register_concat_helpers(&mut h);
+
+        let data = json!({"obj": {"a": 1, "b": 2}});
+
         assert_eq!(
-            h.render_template(
-                r#"{{#concat s arr obj separator=", " distinct=true render_all=true}}<{{#if label}}{{label}}{{else}}{{this}}{{/if}}/>{{/concat}}"#,
-                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            r#"<One/>, <Two/>, <Three/>, <Four/>"#,
-            "Failed to concat literal, array and object using block template"
+            h.render_template(r#"{{concat_keys obj}}"#, &data)
+                .expect("Render error"),
+            "a,b",
+            "Failed to render concat_keys"
         );
         assert_eq!(
-            h.render_template(
-                r#"{{#concat s arr obj separator=", " distinct=true render_all=true quotes=true}}[{{#if label}}{{label}}{{else}}{{this}}{{/if}}]{{/concat}}"#,
-                &json!({"s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            r#""[One]", "[Two]", "[Three]", "[Four]""#,
-            "Failed to concat literal, array and object using block template"
+            h.render_template(r#"{{concat_values obj}}"#, &data)
+                .expect("Render error"),
+            "1,2",
+            "Failed to render concat_values"
         );
         assert_eq!(
             h.render_template(
-                r#"{{#concat s arr obj separator=", " distinct=true render_all=true quotes=true}}[{{#if label}}{{label}}{{else}}{{@root/zero}}{{/if}}]{{/concat}}"#,
-                &json!({"zero":"Zero", "s": "One", "arr": ["One", "Two"], "obj": {"key0":{"label":"Two"},"key1":{"label":"Three"},"key2":{"label":"Four"}}})
-            ).expect("Render error"),
-            r#""[Zero]", "[Two]", "[Three]", "[Four]""#,
-            "Failed to concat literal, array and object using block template"
+                r#"{{concat_entries obj key_value_separator=":" separator=" "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "a:1 b:2",
+            "Failed to render concat_entries"
         );
     }
 }
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+
+use handlebars::{html_escape, Helper, JsonRender, PathAndJson, RenderError, ScopedJson};
+
+use crate::{collect_values, join_values, validate_hash, Params, HASH_PARAMS};
+
+#[derive(Clone, Debug, Default)]
+/// Concat options, the hash parameters of the concat helper outside of a template
+///
+/// # Usage
+///
+/// ```rust
+/// use handlebars_concat::{concat_values, ConcatOptions};
+/// use serde_json::json;
+///
+/// let options = ConcatOptions::new()
+///     .with_separator(", ")
+///     .with_distinct(true)
+///     .with_param("sort", true);
+///
+/// assert_eq!(concat_values(&[json!("b"), json!(["a", "b"]), json!({"c": 1})], &options).expect("Concat error"), "a, b, c");
+/// ```
+///
+/// # Behavior
+///
+/// The options accept any of the hash parameters of [`HandlebarsConcat`](struct@crate::HandlebarsConcat) under the same name and with the same values.
+/// Set as registration time defaults with [`HandlebarsConcat::with_options`](struct@crate::HandlebarsConcat#method.with_options), they apply to every helper call not overriding them in the template.
+///
+pub struct ConcatOptions {
+    params: BTreeMap<String, serde_json::Value>,
+}
+
+impl ConcatOptions {
+    /// Create options without any parameters, same as a helper call without a hash
+    pub const fn new() -> Self {
+        ConcatOptions {
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Collect the hash parameters of any helper call, accepting the same parameters as the concat helper
+    ///
+    /// Allows custom helpers to share the hash parameters of the concat helper, e.g. by passing the options on to [`concat_values`].
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+    /// # extern crate handlebars5 as handlebars;
+    /// use handlebars::{Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderErrorReason};
+    /// use handlebars_concat::{concat_values, ConcatOptions};
+    /// use serde_json::json;
+    ///
+    /// // concatenate the lines of a text argument
+    /// struct Lines;
+    ///
+    /// impl HelperDef for Lines {
+    ///     fn call<'reg: 'rc, 'rc>(&self, h: &Helper<'rc>, _: &'reg Handlebars, _: &'rc Context, _: &mut RenderContext<'reg, 'rc>, out: &mut dyn Output) -> HelperResult {
+    ///         let text = h.param(0).and_then(|p| p.value().as_str()).ok_or(RenderErrorReason::ParamNotFoundForIndex("lines", 0))?;
+    ///         let lines = text.lines().map(|line| json!(line)).collect::<Vec<_>>();
+    ///
+    ///         out.write(&concat_values(&lines, &ConcatOptions::from_helper(h)?)?)?;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut h = Handlebars::new();
+    /// h.register_helper("lines", Box::new(Lines));
+    ///
+    /// assert_eq!(h.render_template(r#"{{lines text separator=", " distinct=true}}"#, &json!({"text": "b\na\nb"})).expect("Render error"), "b, a");
+    /// ```
+    ///
+    /// Unknown hash parameters are skipped, or rejected when the helper call sets `strict=true`.
+    pub fn from_helper(h: &Helper<'_>) -> Result<Self, RenderError> {
+        let helper = Params {
+            helper: Some(h),
+            defaults: &ConcatOptions::new(),
+        };
+
+        if helper.flag("strict") {
+            validate_hash(h)?;
+        }
+
+        Ok(ConcatOptions {
+            params: h
+                .hash()
+                .iter()
+                .filter(|(name, _)| HASH_PARAMS.contains(name))
+                .map(|(name, param)| (name.to_string(), param.value().clone()))
+                .collect(),
+        })
+    }
+
+    /// Set the `separator`
+    pub fn with_separator(self, separator: impl Into<String>) -> Self {
+        self.with_param("separator", separator.into())
+    }
+
+    /// Enable or disable `distinct`
+    pub fn with_distinct(self, distinct: bool) -> Self {
+        self.with_param("distinct", distinct)
+    }
+
+    /// Wrap values in specific quotation mark, same as `quote_char`
+    pub fn with_quotes(self, quote: char) -> Self {
+        self.with_param("quote_char", quote.to_string())
+    }
+
+    /// Set the value of any hash parameter, e.g. `with_param("sort", "desc")`
+    pub fn with_param(mut self, name: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    // value of a specific parameter, if set
+    pub(crate) fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.params.get(name)
+    }
+}
+
+/// Concatenate values the same way as the concat helper, without a template
+///
+/// Every value is treated like a param of the helper, with `options` as its hash parameters.
+/// There is no block template, so the block related parameters `render_all`, `item_template` and `partial` have no effect,
+/// `map` is limited to its built-in transforms, and `escape_output` uses the default HTML escape function of handlebars.
+///
+/// # Errors
+///
+/// Fails whenever the helper would fail to render, e.g. on unresolved values in `strict` mode, on invalid param values such as
+/// a `match` or `exclude_match` regex that does not compile or an unknown `sort` mode, and on registered helpers in `map`.
+///
+pub fn concat_values(
+    values: &[serde_json::Value],
+    options: &ConcatOptions,
+) -> Result<String, RenderError> {
+    let params = Params {
+        helper: None,
+        defaults: options,
+    };
+
+    let values = values
+        .iter()
+        .map(|value| PathAndJson::new(None, ScopedJson::Constant(value)))
+        .collect::<Vec<_>>();
+
+    let output = collect_values(params, &values, None)?;
+
+    let joined = if params.flag("count") {
+        output.len().to_string()
+    } else if output.is_empty() {
+        params
+            .get("default")
+            .map(|s| s.render())
+            .unwrap_or_default()
+    } else {
+        join_values(params, &output)
+    };
+
+    if params.flag("escape_output") {
+        Ok(html_escape(&joined))
+    } else {
+        Ok(joined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let values = [json!("One"), json!(["Two", "One"]), json!({"a": "Three"})];
+
+        assert_eq!(
+            concat_values(&values, &ConcatOptions::new()).expect("Concat error"),
+            "One,Two,One,a",
+            "Failed to concat values"
+        );
+        assert_eq!(
+            concat_values(
+                &values,
+                &ConcatOptions::new()
+                    .with_separator(" ")
+                    .with_distinct(true)
+                    .with_quotes('\'')
+            )
+            .expect("Concat error"),
+            "'One' 'Two' 'a'",
+            "Failed to concat values with options"
+        );
+        assert_eq!(
+            concat_values(&values, &ConcatOptions::new().with_param("count", true))
+                .expect("Concat error"),
+            "4",
+            "Failed to count values"
+        );
+        assert_eq!(
+            concat_values(&[], &ConcatOptions::new().with_param("default", "none"))
+                .expect("Concat error"),
+            "none",
+            "Failed to fall back to default"
+        );
+        assert!(
+            concat_values(&values, &ConcatOptions::new().with_param("pointer", "x")).is_err(),
+            "Failed to reject invalid pointer"
+        );
+
+        // same output as the helper with the same hash parameters
+        let mut h = Handlebars::new();
+        h.register_helper("concat", Box::new(crate::HandlebarsConcat));
+
+        let data = json!({"values": values});
+        let options = ConcatOptions::new()
+            .with_param("mode", "values")
+            .with_param("sort", "desc")
+            .with_param("last_separator", " and ");
+
+        assert_eq!(
+            concat_values(&values, &options).expect("Concat error"),
+            h.render_template(
+                r#"{{concat values.[0] values.[1] values.[2] mode="values" sort="desc" last_separator=" and "}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "Failed to match helper output"
+        );
+    }
+
+    #[test]
+    fn from_helper() {
+        use handlebars::{Context, Handlebars, HelperDef, HelperResult, Output, RenderContext};
+        use serde_json::json;
+
+        // concat helper built on top of the options of the helper call
+        struct Options;
+
+        impl HelperDef for Options {
+            fn call<'reg: 'rc, 'rc>(
+                &self,
+                h: &Helper<'rc>,
+                _: &'reg Handlebars,
+                _: &'rc Context,
+                _: &mut RenderContext<'reg, 'rc>,
+                out: &mut dyn Output,
+            ) -> HelperResult {
+                let values = h
+                    .params()
+                    .iter()
+                    .map(|param| param.value().clone())
+                    .collect::<Vec<_>>();
+
+                out.write(&concat_values(&values, &ConcatOptions::from_helper(h)?)?)?;
+                Ok(())
+            }
+        }
+
+        let mut h = Handlebars::new();
+        h.register_helper("options", Box::new(Options));
+        h.register_helper("concat", Box::new(crate::HandlebarsConcat));
+
+        let data = json!({"a": ["b", "a", "b"], "o": {"y": 1, "x": 2}});
+
+        for hash in [
+            r#"separator=" " distinct=true"#,
+            r#"sort="desc" quotes=true"#,
+            r#"mode="values" last_separator=" and ""#,
+            r#"frequency=true pair_separator="=""#,
+            r#"count=true"#,
+        ] {
+            assert_eq!(
+                h.render_template(&format!("{{{{options a o {}}}}}", hash), &data)
+                    .expect("Render error"),
+                h.render_template(&format!("{{{{concat a o {}}}}}", hash), &data)
+                    .expect("Render error"),
+                "Failed to match helper output with {}",
+                hash
+            );
+        }
+
+        assert_eq!(
+            h.render_template(r#"{{options a unknown=true}}"#, &data)
+                .expect("Render error"),
+            "b,a,b",
+            "Failed to skip unknown hash parameter"
+        );
+        assert!(
+            h.render_template(r#"{{options a unknown=true strict=true}}"#, &data)
+                .is_err(),
+            "Failed to reject unknown hash parameter in strict mode"
+        );
+    }
+}
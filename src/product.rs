@@ -0,0 +1,265 @@
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output, PathAndJson,
+    RenderContext, RenderErrorReason, ScopedJson,
+};
+
+use crate::{collect_values, write_values, BlockRenderer, ConcatError, ConcatOptions, Params};
+
+#[derive(Clone, Copy)]
+/// Cartesian product helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsProduct;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("concat_product", Box::new(HandlebarsProduct));
+///
+/// let data = json!({"platforms": ["linux", "macos"], "archs": ["x64", "arm64"]});
+///
+/// assert_eq!(h.render_template(r#"{{concat_product platforms archs pair_separator="-"}}"#, &data).expect("Render error"), "linux-x64,linux-arm64,macos-x64,macos-arm64");
+/// assert_eq!(h.render_template(r#"{{#concat_product platforms archs separator=" "}}{{this.[0]}}/{{this.[1]}}{{/concat_product}}"#, &data).expect("Render error"), "linux/x64 linux/arm64 macos/x64 macos/arm64");
+/// ```
+///
+/// # Behavior
+///
+/// The helper combines every item of two or more array arguments with every item of the others, the last array varying fastest.
+/// Each combination is rendered through the block template as an array `{{this}}`, with access to the `@index`, `@first` and `@last` local variables.
+/// Without a block template the combination items are joined with the `pair_separator` instead.
+/// Missing and `null` arguments count as empty arrays, leaving no combinations at all, while any other type of argument is a render error.
+///
+/// The combinations are collected like the items of an array param of `concat`, accepting its hash parameters as well, e.g. `distinct`, `sort` or `max_length`.
+///
+/// # Hash parameters
+///
+/// * separator: Set specific string to join combinations with. Default is ","
+/// * pair_separator: Set specific string to join the items of a combination with, when there is no block template. Default is ":"
+/// * max_combinations: Fail to render when there are more combinations than specific number, before rendering any of them. Default is 10000
+///
+pub struct HandlebarsProduct;
+
+// combinations rendered unless configured otherwise, more of them are a render error
+pub(crate) const MAX_COMBINATIONS: usize = 10_000;
+
+impl HelperDef for HandlebarsProduct {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let defaults = ConcatOptions::default();
+        let params = Params {
+            helper: Some(h),
+            defaults: &defaults,
+        };
+
+        let pair_separator = if let Some(s) = h.hash_get("pair_separator") {
+            s.render()
+        } else {
+            ":".to_string()
+        };
+
+        let max = h
+            .hash_get("max_combinations")
+            .and_then(|s| s.render().parse::<usize>().ok())
+            .unwrap_or(MAX_COMBINATIONS);
+
+        let mut arrays = Vec::with_capacity(h.params().len());
+
+        for param in h.params() {
+            match param.value() {
+                serde_json::Value::Null => arrays.push(&[][..]),
+                serde_json::Value::Array(ar) => arrays.push(ar.as_slice()),
+                _ => {
+                    return Err(RenderErrorReason::ParamTypeMismatchForName(
+                        "concat_product",
+                        param.relative_path().cloned().unwrap_or_default(),
+                        "array".to_string(),
+                    )
+                    .into());
+                }
+            }
+        }
+
+        // number of combinations, checked before producing any of them
+        let len = if arrays.is_empty() {
+            0
+        } else {
+            match arrays
+                .iter()
+                .try_fold(1usize, |len, ar| len.checked_mul(ar.len()))
+            {
+                Some(len) if len <= max => len,
+                count => return Err(ConcatError::TooManyCombinations { count, max }.into()),
+            }
+        };
+
+        let mut combinations = Vec::with_capacity(len);
+
+        // position within each array, advanced like an odometer
+        let mut positions = vec![0; arrays.len()];
+
+        for _ in 0..len {
+            let combination = arrays
+                .iter()
+                .zip(&positions)
+                .map(|(ar, position)| &ar[*position]);
+
+            // the block template receives the combination as array, otherwise its items are joined
+            combinations.push(if h.template().is_some() {
+                serde_json::Value::from_iter(combination.cloned())
+            } else {
+                serde_json::Value::from(
+                    combination
+                        .map(|item| item.render())
+                        .collect::<Vec<String>>()
+                        .join(&pair_separator),
+                )
+            });
+
+            for (position, ar) in positions.iter_mut().zip(&arrays).rev() {
+                *position += 1;
+                if *position < ar.len() {
+                    break;
+                }
+                *position = 0;
+            }
+        }
+
+        // collect the combinations the same way as the items of a single array param of concat
+        let values = [PathAndJson::new(
+            None,
+            ScopedJson::Derived(serde_json::Value::Array(combinations)),
+        )];
+
+        let blocks = BlockRenderer {
+            helper: h,
+            template: h.template(),
+            item_template: None,
+            render_all: true,
+            registry: r,
+            ctx,
+            rc,
+        };
+
+        let output = collect_values(params, &values, Some(blocks))?;
+
+        write_values(params, &output, h, r, ctx, rc, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("concat_product", Box::new(HandlebarsProduct));
+
+        let data = json!({"a": ["x", "y"], "b": [1, 2, 3], "c": [true], "s": "x"});
+
+        assert_eq!(
+            h.render_template(r#"{{concat_product a b c separator=" "}}"#, &data)
+                .expect("Render error"),
+            "x:1:true x:2:true x:3:true y:1:true y:2:true y:3:true",
+            "Failed to combine arrays"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat_product a b}}{{@index}}{{this.[0]}}{{this.[1]}}{{#if @last}}.{{/if}}{{/concat_product}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0x1,1x2,2x3,3y1,4y2,5y3.",
+            "Failed to render combinations through block template"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat_product a missing}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to combine missing array"
+        );
+        assert!(
+            h.render_template(r#"{{concat_product a s}}"#, &data)
+                .is_err(),
+            "Failed to reject string argument"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{concat_product b b pair_separator="+" distinct=true sort="desc" limit=3 quotes=true}}"#,
+                &data
+            )
+            .expect("Render error"),
+            r#""3+3","3+2","3+1""#,
+            "Failed to apply concat hash parameters to combinations"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat_product a b max_length=6 overflow="…"}}{{this.[0]}}{{this.[1]}}{{/concat_product}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "x1,x2…",
+            "Failed to cut off rendered combinations"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#concat_product a missing}}{{this}}{{else}}none{{/concat_product}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "none",
+            "Failed to render else block without combinations"
+        );
+
+        let error = h
+            .render_template(r#"{{concat_product a b max_combinations=5}}"#, &data)
+            .expect_err("Failed to reject combinations over the maximum");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::TooManyCombinations {
+                    count: Some(6),
+                    max: 5
+                })
+            ),
+            "Failed to reject combinations over the maximum: {:?}",
+            error
+        );
+
+        let error = h
+            .render_template(
+                r#"{{concat_product n n n n n n n max_combinations=18446744073709551615}}"#,
+                &json!({"n": (0..600).collect::<Vec<_>>()}),
+            )
+            .expect_err("Failed to reject combinations overflowing usize");
+        assert!(
+            matches!(
+                ConcatError::from_render_error(&error),
+                Some(ConcatError::TooManyCombinations { count: None, .. })
+            ),
+            "Failed to reject combinations overflowing usize: {:?}",
+            error
+        );
+        assert!(
+            h.render_template(
+                r#"{{concat_product n n n}}"#,
+                &json!({"n": (0..30).collect::<Vec<_>>()})
+            )
+            .is_err(),
+            "Failed to apply default maximum of combinations"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, RenderContext, RenderError, RenderErrorReason,
+    ScopedJson,
+};
+
+use crate::hash_flag;
+
+#[derive(Clone, Copy)]
+/// Split helper for handlebars-rust, the inverse of concat
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::{HandlebarsConcat, HandlebarsSplit};
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("split", Box::new(HandlebarsSplit));
+/// h.register_helper("concat", Box::new(HandlebarsConcat));
+///
+/// assert_eq!(h.render_template(r#"{{#each (split s)}}<{{this}}>{{/each}}"#, &json!({"s": "One,Two"})).expect("Render error"), "<One><Two>");
+/// assert_eq!(h.render_template(r#"{{concat (split s separator=";") separator=", "}}"#, &json!({"s": "One;Two"})).expect("Render error"), "One, Two");
+/// ```
+///
+/// # Behavior
+///
+/// The helper splits all string arguments by the separator and returns the parts as a single array of strings.
+/// Missing and `null` arguments are skipped, while any other type of argument is a render error.
+/// Empty parts are skipped, just like `concat` skips empty values.
+///
+/// # Hash parameters
+///
+/// * separator: Set specific string to split strings by. Default is ","
+/// * trim: Trim leading and trailing whitespace of each part
+/// * keep_empty: Keep empty parts instead of skipping them
+///
+pub struct HandlebarsSplit;
+
+impl HelperDef for HandlebarsSplit {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let separator = if let Some(s) = h.hash_get("separator") {
+            s.render()
+        } else {
+            ",".to_string()
+        };
+
+        let trim = hash_flag(h, "trim");
+        let keep_empty = hash_flag(h, "keep_empty");
+
+        let mut output: Vec<String> = Vec::new();
+
+        for param in h.params() {
+            let value = match param.value() {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(s) => s,
+                _ => {
+                    return Err(RenderErrorReason::ParamTypeMismatchForName(
+                        "split",
+                        param.relative_path().cloned().unwrap_or_default(),
+                        "string".to_string(),
+                    )
+                    .into());
+                }
+            };
+
+            if value.is_empty() {
+                continue;
+            }
+
+            let parts: Vec<&str> = if separator.is_empty() {
+                // no separator, split into individual characters
+                value
+                    .char_indices()
+                    .map(|(i, c)| &value[i..i + c.len_utf8()])
+                    .collect()
+            } else {
+                value.split(separator.as_str()).collect()
+            };
+
+            for part in parts {
+                let part = if trim { part.trim() } else { part };
+
+                if part.is_empty() && !keep_empty {
+                    continue;
+                }
+
+                output.push(part.to_string());
+            }
+        }
+
+        Ok(ScopedJson::Derived(serde_json::Value::from(output)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("split", Box::new(HandlebarsSplit));
+
+        let data = json!({"s": "a, b,,c", "t": "d", "n": null, "obj": {"a": 1}});
+
+        assert_eq!(
+            h.render_template(r#"{{#each (split s t n)}}<{{this}}>{{/each}}"#, &data)
+                .expect("Render error"),
+            "<a>< b><c><d>",
+            "Failed to split strings"
+        );
+        assert_eq!(
+            h.render_template(r#"{{#each (split s trim=true)}}<{{this}}>{{/each}}"#, &data)
+                .expect("Render error"),
+            "<a><b><c>",
+            "Failed to trim parts"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#each (split s separator=", " keep_empty=true)}}<{{this}}>{{/each}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<a><b,,c>",
+            "Failed to split by custom separator"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#each (split t separator="")}}<{{this}}>{{/each}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<d>",
+            "Failed to split into characters"
+        );
+        assert!(
+            h.render_template(r#"{{split obj}}"#, &data).is_err(),
+            "Failed to reject object argument"
+        );
+    }
+}
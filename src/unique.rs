@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, JsonRender, RenderContext, RenderError, ScopedJson,
+};
+
+use crate::{lookup_field, DistinctMode};
+
+#[derive(Clone, Copy)]
+/// Unique helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsUnique;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("unique", Box::new(HandlebarsUnique));
+///
+/// assert_eq!(h.render_template(r#"{{#each (unique arr "Three")}}<{{this}}>{{/each}}"#, &json!({"arr": ["One", "Two", "One"]})).expect("Render error"), "<One><Two><Three>");
+/// ```
+///
+/// # Behavior
+///
+/// The helper collects the items of array arguments and any other non-null arguments as they are, eliminates duplicates
+/// and returns the remaining values as a single array, so it composes with `#each` and other helpers.
+/// The first occurrence of each value is kept, in order of appearance.
+/// Null params are skipped, while null array items are kept and compare equal to empty strings,
+/// the same way `{{concat arr distinct=true}}` renders them.
+///
+/// # Hash parameters
+///
+/// * distinct: Use `distinct="ci"` to compare values case-insensitively
+/// * distinct_by: Compare array items by the value of a field instead, items missing the field are always kept
+///
+pub struct HandlebarsUnique;
+
+impl HelperDef for HandlebarsUnique {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let mode = h
+            .hash_get("distinct")
+            .map(|mode| DistinctMode::parse(&mode.render()))
+            .unwrap_or(DistinctMode::CaseSensitive);
+
+        let distinct_by = h.hash_get("distinct_by").map(|field| field.render());
+
+        let mut seen = HashSet::new();
+        let mut output = Vec::new();
+
+        for param in h.params() {
+            let items = match param.value() {
+                serde_json::Value::Null => continue,
+                serde_json::Value::Array(ar) => ar.as_slice(),
+                value => std::slice::from_ref(value),
+            };
+
+            for item in items {
+                let key = match &distinct_by {
+                    Some(field) => match lookup_field(item, field) {
+                        Some(key) => key.render(),
+                        None => {
+                            output.push(item.clone());
+                            continue;
+                        }
+                    },
+                    // nested values render lossy, compare them as JSON instead
+                    None if item.is_array() || item.is_object() => item.to_string(),
+                    None => item.render(),
+                };
+
+                let key = match mode {
+                    DistinctMode::CaseSensitive => key,
+                    DistinctMode::CaseInsensitive => key.to_lowercase(),
+                };
+
+                if seen.insert(key) {
+                    output.push(item.clone());
+                }
+            }
+        }
+
+        Ok(ScopedJson::Derived(serde_json::Value::from(output)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("unique", Box::new(HandlebarsUnique));
+
+        let data = json!({
+            "arr": ["a", "B", "b", 1, "1", null],
+            "objs": [{"id": 1, "name": "x"}, {"id": 2, "name": "y"}, {"id": 1, "name": "z"}, {"name": "w"}]
+        });
+
+        assert_eq!(
+            h.render_template(r#"{{#each (unique arr "a")}}<{{this}}>{{/each}}"#, &data)
+                .expect("Render error"),
+            "<a><B><b><1><>",
+            "Failed to eliminate duplicates"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#each (unique arr distinct="ci")}}<{{this}}>{{/each}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<a><B><1><>",
+            "Failed to eliminate duplicates case-insensitively"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#each (unique objs distinct_by="id")}}<{{name}}>{{/each}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "<x><y><w>",
+            "Failed to eliminate duplicates by field"
+        );
+
+        h.register_helper("concat", Box::new(crate::HandlebarsConcat));
+
+        let data = json!({"arr": ["a", null, "", "a", null, "b"], "missing": null});
+
+        assert_eq!(
+            h.render_template(r#"{{concat (unique missing arr)}}"#, &data)
+                .expect("Render error"),
+            h.render_template(r#"{{concat missing arr distinct=true}}"#, &data)
+                .expect("Render error"),
+            "Failed to handle nulls the same way as concat"
+        );
+        assert_eq!(
+            h.render_template(r#"{{concat (unique missing arr)}}"#, &data)
+                .expect("Render error"),
+            "a,,b",
+            "Failed to keep null array items"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use handlebars::{
+    BlockContext, Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output,
+    RenderContext, RenderErrorReason, Renderable,
+};
+
+use crate::{set_block_locals, BlockOutput};
+
+#[derive(Clone, Copy)]
+/// Zip helper for handlebars-rust
+///
+/// # Registration
+///
+/// ```rust
+/// # #[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+/// # extern crate handlebars5 as handlebars;
+/// use handlebars::Handlebars;
+/// use handlebars_concat::HandlebarsZip;
+/// use serde_json::json;
+///
+/// let mut h = Handlebars::new();
+/// h.register_helper("zip", Box::new(HandlebarsZip));
+///
+/// let data = json!({"names": ["a", "b"], "values": [1, 2]});
+///
+/// assert_eq!(h.render_template(r#"{{zip names values separator=", "}}"#, &data).expect("Render error"), "a:1, b:2");
+/// assert_eq!(h.render_template(r#"{{#zip names values separator=", "}}{{this.[0]}}={{this.[1]}}{{/zip}}"#, &data).expect("Render error"), "a=1, b=2");
+/// ```
+///
+/// # Behavior
+///
+/// The helper pairs the items of two or more array arguments element-wise, stopping at the end of the shortest array.
+/// Each tuple is rendered through the block template as an array `{{this}}`, with access to the `@index`, `@first` and `@last` local variables.
+/// Without a block template the tuple items are joined with the `pair_separator` instead.
+/// Missing and `null` arguments count as empty arrays, while any other type of argument is a render error.
+///
+/// # Hash parameters
+///
+/// * separator: Set specific string to join tuples with. Default is ","
+/// * pair_separator: Set specific string to join the items of a tuple with, when there is no block template. Default is ":"
+///
+pub struct HandlebarsZip;
+
+impl HelperDef for HandlebarsZip {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let separator = if let Some(s) = h.hash_get("separator") {
+            s.render()
+        } else {
+            ",".to_string()
+        };
+
+        let pair_separator = if let Some(s) = h.hash_get("pair_separator") {
+            s.render()
+        } else {
+            ":".to_string()
+        };
+
+        let mut arrays = Vec::with_capacity(h.params().len());
+
+        for param in h.params() {
+            match param.value() {
+                serde_json::Value::Null => arrays.push(&[][..]),
+                serde_json::Value::Array(ar) => arrays.push(ar.as_slice()),
+                _ => {
+                    return Err(RenderErrorReason::ParamTypeMismatchForName(
+                        "zip",
+                        param.relative_path().cloned().unwrap_or_default(),
+                        "array".to_string(),
+                    )
+                    .into());
+                }
+            }
+        }
+
+        let len = arrays.iter().map(|ar| ar.len()).min().unwrap_or(0);
+        let mut output = Vec::with_capacity(len);
+        let mut content = BlockOutput::default();
+
+        for index in 0..len {
+            let tuple = arrays.iter().map(|ar| &ar[index]);
+
+            if let Some(template) = h.template() {
+                let mut block = BlockContext::new();
+                block.set_base_value(serde_json::Value::from_iter(tuple.cloned()));
+                set_block_locals(&mut block, index, len);
+
+                rc.push_block(block);
+                template.render(r, ctx, rc, &mut content)?;
+                rc.pop_block();
+
+                output.push(content.take());
+            } else {
+                output.push(
+                    tuple
+                        .map(|item| item.render())
+                        .collect::<Vec<String>>()
+                        .join(&pair_separator),
+                );
+            }
+        }
+
+        out.write(&output.join(&separator))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        use handlebars::Handlebars;
+        use serde_json::json;
+
+        let mut h = Handlebars::new();
+        h.register_helper("zip", Box::new(HandlebarsZip));
+
+        let data = json!({"a": ["x", "y", "z"], "b": [1, 2], "c": [true, false], "s": "x"});
+
+        assert_eq!(
+            h.render_template(r#"{{zip a b c pair_separator="-" separator=" "}}"#, &data)
+                .expect("Render error"),
+            "x-1-true y-2-false",
+            "Failed to zip arrays"
+        );
+        assert_eq!(
+            h.render_template(
+                r#"{{#zip a b}}{{@index}}{{this.[0]}}{{this.[1]}}{{#if @last}}.{{/if}}{{/zip}}"#,
+                &data
+            )
+            .expect("Render error"),
+            "0x1,1y2.",
+            "Failed to render tuples through block template"
+        );
+        assert_eq!(
+            h.render_template(r#"{{zip a missing}}"#, &data)
+                .expect("Render error"),
+            "",
+            "Failed to zip missing array"
+        );
+        assert!(
+            h.render_template(r#"{{zip a s}}"#, &data).is_err(),
+            "Failed to reject string argument"
+        );
+    }
+}
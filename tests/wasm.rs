@@ -0,0 +1,219 @@
+// Runs on the native targets as well as on `wasm32-unknown-unknown`, the outputs must be identical on both:
+// `wasm-pack test --node -- --all-features --test wasm`
+
+// handlebars 5 under the same name as handlebars 6, the same way as the library does
+#[cfg(all(feature = "handlebars5", not(feature = "handlebars6")))]
+extern crate handlebars5 as handlebars;
+
+use handlebars::Handlebars;
+use handlebars_concat::{concat_values, register_concat_helpers, ConcatOptions};
+use serde_json::json;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn registry() -> Handlebars<'static> {
+    let mut h = Handlebars::new();
+    register_concat_helpers(&mut h);
+    h
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn concat() {
+    let h = registry();
+
+    let data = json!({
+        "s": "One",
+        "arr": ["Two", "One", "Three"],
+        "obj": {"key0": {"label": "Four"}, "key1": {"label": "Five"}}
+    });
+
+    assert_eq!(
+        h.render_template(r#"{{concat s arr obj separator=", "}}"#, &data)
+            .expect("Render error"),
+        "One, Two, One, Three, key0, key1",
+        "Failed to concat values"
+    );
+    assert_eq!(
+        h.render_template(
+            r#"{{#concat s arr obj separator=", " distinct=true render_all=true}}<{{#if label}}{{label}}{{else}}{{this}}{{/if}}/>{{/concat}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "<One/>, <Two/>, <Three/>, <Four/>, <Five/>",
+        "Failed to render values through block template"
+    );
+    assert_eq!(
+        h.render_template(
+            r#"{{concat arr sort=true quotes=true single_quote=true last_separator=" and "}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "'One','Three' and 'Two'",
+        "Failed to sort and quote values"
+    );
+    assert_eq!(
+        h.render_template(r#"{{concat s arr frequency=true}}"#, &data)
+            .expect("Render error"),
+        "One:2,Two:1,Three:1",
+        "Failed to count values"
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn numbers() {
+    let h = registry();
+
+    // pointer sized integers are 32 bits wide on wasm32, large numbers must not be cut off
+    let data = json!({"n": [1234567.891, 4294967296_u64, -0.5, 18446744073709551615_u64]});
+
+    assert_eq!(
+        h.render_template(
+            r#"{{concat n precision=2 thousands_separator="_" separator=" "}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "1_234_567.89 4_294_967_296.00 -0.50 18_446_744_073_709_551_616.00",
+        "Failed to format numbers"
+    );
+    assert_eq!(
+        h.render_template(r#"{{concat n sort="numeric" separator=" "}}"#, &data)
+            .expect("Render error"),
+        "-0.5 1234567.891 4294967296 18446744073709551615",
+        "Failed to sort numbers"
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn helpers() {
+    let h = registry();
+
+    let data = json!({
+        "csv": "a, b,,c",
+        "arr": ["a", "b", "a"],
+        "people": [{"name": "Ann", "team": "x"}, {"name": "Bob", "team": "y"}, {"name": "Cid", "team": "x"}],
+        "x": [1, 2],
+        "y": ["one", "two"]
+    });
+
+    assert_eq!(
+        h.render_template(
+            r#"{{#each (split csv trim=true)}}[{{this}}]{{/each}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "[a][b][c]",
+        "Failed to split"
+    );
+    assert_eq!(
+        h.render_template(r#"{{join (unique arr) separator="-"}}"#, &data)
+            .expect("Render error"),
+        "a-b",
+        "Failed to join unique values"
+    );
+    assert_eq!(
+        h.render_template(
+            r#"{{#group_by people by="team" separator=" "}}{{@group_key}}={{#each this}}{{name}}{{/each}}{{/group_by}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "x=AnnCid y=Bob",
+        "Failed to group values"
+    );
+    assert_eq!(
+        h.render_template(
+            r#"{{zip x y}} {{concat_product x y pair_separator="-"}}"#,
+            &data
+        )
+        .expect("Render error"),
+        "1:one,2:two 1-one,1-two,2-one,2-two",
+        "Failed to zip and combine values"
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn options() {
+    let options = ConcatOptions::new()
+        .with_separator(" | ")
+        .with_distinct(true)
+        .with_param("sort", "desc");
+
+    assert_eq!(
+        concat_values(&[json!("b"), json!(["a", "c", "b"])], &options).expect("Concat error"),
+        "c | b | a",
+        "Failed to concat values with options"
+    );
+}
+
+#[cfg(feature = "regex")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn regex() {
+    let h = registry();
+
+    assert_eq!(
+        h.render_template(
+            r#"{{concat arr match="^[a-z]+[0-9]$"}}"#,
+            &json!({"arr": ["ab1", "AB2", "cd3", "e"]})
+        )
+        .expect("Render error"),
+        "ab1,cd3",
+        "Failed to match values"
+    );
+}
+
+#[cfg(feature = "icu")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn locale() {
+    let h = registry();
+
+    assert_eq!(
+        h.render_template(
+            r#"{{concat arr sort=true locale="sv"}}"#,
+            &json!({"arr": ["ö", "z", "a"]})
+        )
+        .expect("Render error"),
+        "a,z,ö",
+        "Failed to sort values by locale"
+    );
+}
+
+#[cfg(feature = "rhai")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn script() {
+    let h = registry();
+
+    assert_eq!(
+        h.render_template(
+            r#"{{concat arr filter_script="value.len() > 1" sort_script="a.len() - b.len()"}}"#,
+            &json!({"arr": ["ccc", "a", "bb"]})
+        )
+        .expect("Render error"),
+        "bb,ccc",
+        "Failed to filter and sort values through scripts"
+    );
+}
+
+// without threads rayon falls back to the current thread, rendering still succeeds
+#[cfg(all(feature = "rayon", feature = "handlebars6"))]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn parallel() {
+    let h = registry();
+
+    assert_eq!(
+        h.render_template(
+            r#"{{#concat arr parallel=true render_all=true}}<{{this}}>{{/concat}}"#,
+            &json!({"arr": [1, 2, 3, 4]})
+        )
+        .expect("Render error"),
+        "<1>,<2>,<3>,<4>",
+        "Failed to render values in parallel"
+    );
+}